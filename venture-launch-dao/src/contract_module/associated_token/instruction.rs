@@ -0,0 +1,54 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction}, pubkey::Pubkey
+};
+use std::str::FromStr;
+
+pub fn create_associated_token_program_instruction(payer: &Pubkey, ata: &Pubkey, mint: &Pubkey) -> Instruction {
+    create_associated_token_program_instruction_for_owner(payer, ata, payer, mint)
+}
+
+/// Creates `ata`, the associated token account of `owner` for `mint`, paid by `payer`
+pub fn create_associated_token_program_instruction_for_owner(payer: &Pubkey, ata: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    associated_token_program_instruction(payer, ata, owner, mint, vec![])
+}
+
+/// Same as `create_associated_token_program_instruction_for_owner`, but succeeds when `ata` already exists
+pub fn create_associated_token_program_idempotent_instruction_for_owner(payer: &Pubkey, ata: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    // CreateIdempotent, the empty data of the legacy create is Create
+    associated_token_program_instruction(payer, ata, owner, mint, vec![1])
+}
+
+fn associated_token_program_instruction(payer: &Pubkey, ata: &Pubkey, owner: &Pubkey, mint: &Pubkey, instruction_data: Vec<u8>) -> Instruction {
+    let associated_token_program = Pubkey::from_str("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL").unwrap();
+    solana_sdk::instruction::Instruction::new_with_bytes(
+        associated_token_program.clone(),
+        &instruction_data[..],
+        vec![
+            AccountMeta::new(payer.clone(), true),
+            AccountMeta::new(ata.clone(), false),
+            AccountMeta::new_readonly(owner.clone(), false),
+            AccountMeta::new_readonly(mint.clone(), false),
+            AccountMeta::new_readonly(solana_sdk::system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract_module::associated_token::utils::get_associated_token_address;
+
+    #[test]
+    fn idempotent_instruction_matches_associated_token_program() {
+        let payer = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata = get_associated_token_address(&mint, &owner);
+
+        assert_eq!(
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(&payer, &owner, &mint, &spl_token::id()),
+            create_associated_token_program_idempotent_instruction_for_owner(&payer, &ata, &owner, &mint)
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction, program_pack::Pack, pubkey::Pubkey, system_instruction
+};
+use spl_token::state::Account as TokenAccount;
+
+use super::instruction;
+use super::utils::get_associated_token_address;
+use crate::contract_module::error::VentureLaunchError;
+
+/// wSOL account of `owner`, its associated token account of the native mint
+pub fn get_wrapped_sol_address(owner: &Pubkey) -> Pubkey {
+    get_associated_token_address(&spl_token::native_mint::id(), owner)
+}
+
+/// Checks `account` at `address` holds wrapped SOL of `owner`
+fn check_wrapped_sol_account(account: &TokenAccount, address: &Pubkey, owner: &Pubkey) -> Result<(), VentureLaunchError> {
+    if !account.is_native() || account.mint != spl_token::native_mint::id() || account.owner != *owner {
+        return Err(VentureLaunchError::InvalidWrappedSolAccount(*address));
+    }
+
+    Ok(())
+}
+
+/// wSOL account of `owner`, `None` until it is created
+pub async fn get_wrapped_sol_account(rpc_client: &RpcClient, owner: &Pubkey) -> Result<Option<TokenAccount>, VentureLaunchError> {
+    let address = get_wrapped_sol_address(owner);
+    let account = rpc_client
+        .get_account_with_commitment(&address, rpc_client.commitment())
+        .await
+        .map_err(|_| VentureLaunchError::FailedToFetchAccount(address))?
+        .value;
+
+    let Some(account) = account else {
+        return Ok(None);
+    };
+    let token_account = TokenAccount::unpack(&account.data).map_err(|_| VentureLaunchError::InvalidWrappedSolAccount(address))?;
+    check_wrapped_sol_account(&token_account, &address, owner)?;
+
+    Ok(Some(token_account))
+}
+
+/// Wrapped SOL `owner` holds, zero without a wSOL account
+pub async fn get_wrapped_sol_balance(rpc_client: &RpcClient, owner: &Pubkey) -> Result<u64, VentureLaunchError> {
+    Ok(get_wrapped_sol_account(rpc_client, owner).await?.map(|account| account.amount).unwrap_or_default())
+}
+
+/// Wraps `amount` lamports of `payer` into the wSOL account of `owner`, creating it first when missing.
+/// Only `payer` signs, so `owner` can be a program derived address like a squads vault
+pub async fn instructions_wrap_sol(rpc_client: &RpcClient, payer: &Pubkey, owner: &Pubkey, amount: u64) -> Result<Vec<Instruction>, VentureLaunchError> {
+    let address = get_wrapped_sol_address(owner);
+    // An existing account at the address has to be wSOL of `owner`, the transfer would be lost otherwise
+    get_wrapped_sol_account(rpc_client, owner).await?;
+
+    Ok(vec![
+        instruction::create_associated_token_program_idempotent_instruction_for_owner(
+            payer,
+            &address,
+            owner,
+            &spl_token::native_mint::id()
+        ),
+        system_instruction::transfer(payer, &address, amount),
+        spl_token::instruction::sync_native(&spl_token::id(), &address)
+            .map_err(|err| VentureLaunchError::FailedToBuildInstruction(err.to_string()))?,
+    ])
+}
+
+/// Brings the token amount of the wSOL account of `owner` in line with lamports sent to it directly
+pub async fn instruction_sync_native(rpc_client: &RpcClient, owner: &Pubkey) -> Result<Instruction, VentureLaunchError> {
+    let address = get_wrapped_sol_address(owner);
+    if get_wrapped_sol_account(rpc_client, owner).await?.is_none() {
+        return Err(VentureLaunchError::WrappedSolAccountNotFound(address));
+    }
+
+    spl_token::instruction::sync_native(&spl_token::id(), &address)
+        .map_err(|err| VentureLaunchError::FailedToBuildInstruction(err.to_string()))
+}
+
+/// Unwraps everything in the wSOL account of `owner` by closing it, its lamports and rent go to `destination`.
+/// Signed by `owner`
+pub async fn instruction_unwrap_sol(rpc_client: &RpcClient, owner: &Pubkey, destination: &Pubkey) -> Result<Instruction, VentureLaunchError> {
+    let address = get_wrapped_sol_address(owner);
+    if get_wrapped_sol_account(rpc_client, owner).await?.is_none() {
+        return Err(VentureLaunchError::WrappedSolAccountNotFound(address));
+    }
+
+    instruction_close_wrapped_sol_account(owner, destination)
+}
+
+/// Same close `instruction_unwrap_sol` builds, without checking the account, needed to execute a vault transaction
+/// once the account may already hold a different amount
+pub fn instruction_close_wrapped_sol_account(owner: &Pubkey, destination: &Pubkey) -> Result<Instruction, VentureLaunchError> {
+    spl_token::instruction::close_account(&spl_token::id(), &get_wrapped_sol_address(owner), destination, owner, &[])
+        .map_err(|err| VentureLaunchError::FailedToBuildInstruction(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::program_option::COption;
+
+    fn wrapped_sol_account(owner: Pubkey) -> TokenAccount {
+        TokenAccount {
+            mint: spl_token::native_mint::id(),
+            owner,
+            amount: 1_000,
+            is_native: COption::Some(2_039_280),
+            state: spl_token::state::AccountState::Initialized,
+            ..TokenAccount::default()
+        }
+    }
+
+    #[test]
+    fn wrapped_sol_address_is_the_associated_token_account() {
+        let owner = Pubkey::new_unique();
+
+        assert_eq!(
+            spl_associated_token_account::get_associated_token_address(&owner, &spl_token::native_mint::id()),
+            get_wrapped_sol_address(&owner)
+        );
+    }
+
+    #[test]
+    fn checks_wrapped_sol_account() {
+        let owner = Pubkey::new_unique();
+        let address = get_wrapped_sol_address(&owner);
+
+        assert!(check_wrapped_sol_account(&wrapped_sol_account(owner), &address, &owner).is_ok());
+        assert!(check_wrapped_sol_account(&wrapped_sol_account(Pubkey::new_unique()), &address, &owner).is_err());
+        assert!(check_wrapped_sol_account(
+            &TokenAccount { is_native: COption::None, ..wrapped_sol_account(owner) },
+            &address,
+            &owner
+        ).is_err());
+    }
+
+    #[test]
+    fn unwrap_closes_to_destination() {
+        let owner = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let instruction = instruction_close_wrapped_sol_account(&owner, &destination).unwrap();
+
+        assert_eq!(spl_token::id(), instruction.program_id);
+        assert_eq!(get_wrapped_sol_address(&owner), instruction.accounts[0].pubkey);
+        assert_eq!(destination, instruction.accounts[1].pubkey);
+        assert!(instruction.accounts[2].is_signer);
+    }
+}
@@ -0,0 +1,30 @@
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone)]
+pub enum VentureLaunchError {
+    #[error("Failed to fetch rent exemption")]
+    FailedToFetchRent,
+    #[error("Failed to fetch account {0}")]
+    FailedToFetchAccount(Pubkey),
+    #[error("{0} is not a token account")]
+    InvalidVaultAccount(Pubkey),
+    #[error("Failed to build instruction: {0}")]
+    FailedToBuildInstruction(String),
+    #[error("Data account {data_account} is owned by {owner}, not by the program")]
+    WrongDataAccountOwner { data_account: Pubkey, owner: Pubkey },
+    #[error("{0} is not a vault data account")]
+    InvalidDataAccount(Pubkey),
+    #[error("Data account {0} is not initialized")]
+    DataAccountNotInitialized(Pubkey),
+    #[error("Data account {data_account} tracks vault {vault_account}")]
+    VaultMismatch { data_account: Pubkey, vault_account: Pubkey },
+    #[error("Invalid instruction data: {0}")]
+    InvalidInstructionData(String),
+    #[error("Instruction has only {0} accounts")]
+    NotEnoughAccounts(usize),
+    #[error("wSOL account {0} does not exist")]
+    WrappedSolAccountNotFound(Pubkey),
+    #[error("{0} is not a wSOL account of its owner")]
+    InvalidWrappedSolAccount(Pubkey),
+}
@@ -0,0 +1,331 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction}, pubkey::Pubkey
+};
+
+use super::error::VentureLaunchError;
+use super::venture_launch::VentureLaunch;
+
+/// Seed of the program derived address that owns every vault token account
+pub const PDA_SEED: &[u8] = b"cryptotracker";
+
+/// Address the program signs vault transfers with, together with its bump
+pub fn get_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PDA_SEED], program_id)
+}
+
+/// Instructions of the VentureLaunch program. Borsh encodes the variant as one tag byte followed by
+/// the little-endian amount, the layout the program reads
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VentureLaunchInstruction {
+    /// Takes over a new vault token account and initializes the data account tracking it.
+    /// Accounts are listed by `CreateVaultAccounts`
+    CreateVault,
+    /// Moves `amount` from a token account of the depositor to the vault.
+    /// Accounts are listed by `DepositAccounts`
+    Deposit { amount: u64 },
+    /// Moves `amount` from the vault to a token account, signed by the program derived address.
+    /// Accounts are listed by `WithdrawAccounts`
+    Withdraw { amount: u64 },
+}
+
+impl VentureLaunchInstruction {
+    pub fn pack(&self) -> Vec<u8> {
+        borsh::to_vec(self).expect("instruction serializes into a vec")
+    }
+
+    pub fn unpack(data: &[u8]) -> Result<Self, VentureLaunchError> {
+        Self::try_from_slice(data).map_err(|err| VentureLaunchError::InvalidInstructionData(err.to_string()))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CreateVault => "CreateVault",
+            Self::Deposit { .. } => "Deposit",
+            Self::Withdraw { .. } => "Withdraw",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CreateVaultAccounts {
+    /// Signer, becomes the initializer recorded in the data account
+    pub initializer: Pubkey,
+    /// Writable, token account handed over to the program
+    pub vault_account: Pubkey,
+    /// Writable, program owned account the vault state is stored in
+    pub data_account: Pubkey,
+    /// Program owning the mint of `vault_account`
+    pub token_program: Pubkey,
+}
+
+impl CreateVaultAccounts {
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.initializer, true),
+            AccountMeta::new(self.vault_account, false),
+            AccountMeta::new(self.data_account, false),
+            AccountMeta::new_readonly(solana_sdk::sysvar::rent::id(), false),
+            AccountMeta::new_readonly(self.token_program, false),
+        ]
+    }
+
+    pub fn from_accounts(accounts: &[Pubkey]) -> Result<Self, VentureLaunchError> {
+        match accounts {
+            [initializer, vault_account, data_account, _rent, token_program, ..] => Ok(Self {
+                initializer: *initializer,
+                vault_account: *vault_account,
+                data_account: *data_account,
+                token_program: *token_program,
+            }),
+            _ => Err(VentureLaunchError::NotEnoughAccounts(accounts.len())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepositAccounts {
+    /// Signer, owner of `deposit_account`
+    pub depositor: Pubkey,
+    /// Writable, token account the deposit is taken from
+    pub deposit_account: Pubkey,
+    /// Writable, vault token account
+    pub vault_account: Pubkey,
+    /// Writable, data account of the vault
+    pub data_account: Pubkey,
+}
+
+impl DepositAccounts {
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.depositor, true),
+            AccountMeta::new(self.deposit_account, false),
+            AccountMeta::new(self.vault_account, false),
+            AccountMeta::new(self.data_account, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ]
+    }
+
+    pub fn from_accounts(accounts: &[Pubkey]) -> Result<Self, VentureLaunchError> {
+        match accounts {
+            [depositor, deposit_account, vault_account, data_account, _token_program, ..] => Ok(Self {
+                depositor: *depositor,
+                deposit_account: *deposit_account,
+                vault_account: *vault_account,
+                data_account: *data_account,
+            }),
+            _ => Err(VentureLaunchError::NotEnoughAccounts(accounts.len())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithdrawAccounts {
+    /// Signer, has to be the initializer of the vault
+    pub caller: Pubkey,
+    /// Writable, token account the withdrawal goes to
+    pub receive_account: Pubkey,
+    /// Writable, vault token account
+    pub vault_account: Pubkey,
+    /// Writable, data account of the vault
+    pub data_account: Pubkey,
+    /// Program owning the mint of `vault_account`
+    pub token_program: Pubkey,
+    /// Program derived address owning the vault, see `get_pda`
+    pub pda_account: Pubkey,
+}
+
+impl WithdrawAccounts {
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.caller, true),
+            AccountMeta::new(self.receive_account, false),
+            AccountMeta::new(self.vault_account, false),
+            AccountMeta::new(self.data_account, false),
+            AccountMeta::new_readonly(self.token_program, false),
+            AccountMeta::new(self.pda_account, false),
+        ]
+    }
+
+    pub fn from_accounts(accounts: &[Pubkey]) -> Result<Self, VentureLaunchError> {
+        match accounts {
+            [caller, receive_account, vault_account, data_account, token_program, pda_account, ..] => Ok(Self {
+                caller: *caller,
+                receive_account: *receive_account,
+                vault_account: *vault_account,
+                data_account: *data_account,
+                token_program: *token_program,
+                pda_account: *pda_account,
+            }),
+            _ => Err(VentureLaunchError::NotEnoughAccounts(accounts.len())),
+        }
+    }
+}
+
+/// Accounts of a decoded instruction by their role
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VentureLaunchAccounts {
+    CreateVault(CreateVaultAccounts),
+    Deposit(DepositAccounts),
+    Withdraw(WithdrawAccounts),
+}
+
+/// Reads back an instruction of the program, e.g. one found in a historical transaction
+pub fn decode_instruction(data: &[u8], accounts: &[Pubkey]) -> Result<(VentureLaunchInstruction, VentureLaunchAccounts), VentureLaunchError> {
+    let instruction = VentureLaunchInstruction::unpack(data)?;
+    let accounts = match instruction {
+        VentureLaunchInstruction::CreateVault => VentureLaunchAccounts::CreateVault(CreateVaultAccounts::from_accounts(accounts)?),
+        VentureLaunchInstruction::Deposit { .. } => VentureLaunchAccounts::Deposit(DepositAccounts::from_accounts(accounts)?),
+        VentureLaunchInstruction::Withdraw { .. } => VentureLaunchAccounts::Withdraw(WithdrawAccounts::from_accounts(accounts)?),
+    };
+
+    Ok((instruction, accounts))
+}
+
+pub fn instruction_create_vault(program_id: &Pubkey, accounts: &CreateVaultAccounts) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &VentureLaunchInstruction::CreateVault.pack(), accounts.to_account_metas())
+}
+
+pub fn instruction_deposit(program_id: &Pubkey, accounts: &DepositAccounts, amount: u64) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &VentureLaunchInstruction::Deposit { amount }.pack(), accounts.to_account_metas())
+}
+
+pub fn instruction_withdraw(program_id: &Pubkey, accounts: &WithdrawAccounts, amount: u64) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &VentureLaunchInstruction::Withdraw { amount }.pack(), accounts.to_account_metas())
+}
+
+pub fn create_vault(
+    vl: &VentureLaunch,
+    payer: &Pubkey,
+) -> Instruction {
+    let accounts = CreateVaultAccounts {
+        initializer: *payer,
+        vault_account: vl.vault_account,
+        data_account: vl.data_account,
+        token_program: spl_token::id(),
+    };
+
+    instruction_create_vault(&vl.program_id, &accounts)
+}
+
+pub fn deposit(
+    vl: &VentureLaunch,
+    payer: &Pubkey,
+    ata_account: &Pubkey,
+    amount: u64
+) -> Instruction {
+    let accounts = DepositAccounts {
+        depositor: *payer,
+        deposit_account: *ata_account,
+        vault_account: vl.vault_account,
+        data_account: vl.data_account,
+    };
+
+    instruction_deposit(&vl.program_id, &accounts, amount)
+}
+
+pub fn withdraw(
+    vl: &VentureLaunch,
+    payer: &Pubkey,
+    receive_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = WithdrawAccounts {
+        caller: *payer,
+        receive_account: *receive_account,
+        vault_account: vl.vault_account,
+        data_account: vl.data_account,
+        token_program: spl_token::id(),
+        pda_account: get_pda(&vl.program_id).0,
+    };
+
+    instruction_withdraw(&vl.program_id, &accounts, amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(instruction: &Instruction) -> Vec<Pubkey> {
+        instruction.accounts.iter().map(|meta| meta.pubkey).collect()
+    }
+
+    fn venture_launch() -> VentureLaunch {
+        VentureLaunch::new(
+            solana_client::nonblocking::rpc_client::RpcClient::new("http://127.0.0.1:8899".to_string()),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        )
+    }
+
+    #[test]
+    fn instruction_data_keeps_program_layout() {
+        assert_eq!(vec![0], VentureLaunchInstruction::CreateVault.pack());
+
+        let mut deposit = vec![1];
+        deposit.extend_from_slice(&1_000_000u64.to_le_bytes());
+        assert_eq!(deposit, VentureLaunchInstruction::Deposit { amount: 1_000_000 }.pack());
+
+        let mut withdraw = vec![2];
+        withdraw.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(withdraw, VentureLaunchInstruction::Withdraw { amount: u64::MAX }.pack());
+    }
+
+    #[test]
+    fn instruction_data_round_trips() {
+        for instruction in [
+            VentureLaunchInstruction::CreateVault,
+            VentureLaunchInstruction::Deposit { amount: 42 },
+            VentureLaunchInstruction::Withdraw { amount: 7 },
+        ] {
+            assert_eq!(instruction, VentureLaunchInstruction::unpack(&instruction.pack()).unwrap());
+        }
+
+        assert!(VentureLaunchInstruction::unpack(&[]).is_err());
+        assert!(VentureLaunchInstruction::unpack(&[3]).is_err());
+        assert!(VentureLaunchInstruction::unpack(&[1, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn built_instructions_decode_to_their_accounts() {
+        let vl = venture_launch();
+        let payer = Pubkey::new_unique();
+        let token_account = Pubkey::new_unique();
+
+        let ix = create_vault(&vl, &payer);
+        assert_eq!(
+            (
+                VentureLaunchInstruction::CreateVault,
+                VentureLaunchAccounts::CreateVault(CreateVaultAccounts {
+                    initializer: payer,
+                    vault_account: vl.vault_account,
+                    data_account: vl.data_account,
+                    token_program: spl_token::id(),
+                })
+            ),
+            decode_instruction(&ix.data, &keys(&ix)).unwrap()
+        );
+
+        let ix = deposit(&vl, &payer, &token_account, 5);
+        match decode_instruction(&ix.data, &keys(&ix)).unwrap() {
+            (VentureLaunchInstruction::Deposit { amount: 5 }, VentureLaunchAccounts::Deposit(accounts)) => {
+                assert_eq!(token_account, accounts.deposit_account);
+                assert_eq!(accounts.to_account_metas(), ix.accounts);
+            },
+            decoded => panic!("unexpected {decoded:?}"),
+        }
+
+        let ix = withdraw(&vl, &payer, &token_account, 5);
+        match decode_instruction(&ix.data, &keys(&ix)).unwrap() {
+            (VentureLaunchInstruction::Withdraw { amount: 5 }, VentureLaunchAccounts::Withdraw(accounts)) => {
+                assert_eq!(get_pda(&vl.program_id).0, accounts.pda_account);
+                assert_eq!(accounts.to_account_metas(), ix.accounts);
+            },
+            decoded => panic!("unexpected {decoded:?}"),
+        }
+
+        assert!(decode_instruction(&ix.data, &keys(&ix)[..3]).is_err());
+    }
+}
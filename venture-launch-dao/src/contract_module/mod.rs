@@ -1,4 +1,6 @@
 pub mod venture_launch;
 pub mod instruction;
 pub mod associated_token;
-mod test;
\ No newline at end of file
+pub mod error;
+pub mod state;
+mod test;
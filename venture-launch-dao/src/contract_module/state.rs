@@ -0,0 +1,136 @@
+use solana_sdk::{
+    account::Account,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+use super::error::VentureLaunchError;
+
+/// Data account of a vault as the program lays it out:
+/// initialized flag, initializer, vault token account and the deposited amount, 73 bytes in total
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CryptoTracker {
+    pub is_initialized: bool,
+    pub initializer_pubkey: Pubkey,
+    pub vault_account_pubkey: Pubkey,
+    pub amount: u64,
+}
+
+impl Sealed for CryptoTracker {}
+
+impl IsInitialized for CryptoTracker {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for CryptoTracker {
+    const LEN: usize = 1 + 32 + 32 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        dst[0] = self.is_initialized as u8;
+        dst[1..33].copy_from_slice(self.initializer_pubkey.as_ref());
+        dst[33..65].copy_from_slice(self.vault_account_pubkey.as_ref());
+        dst[65..73].copy_from_slice(&self.amount.to_le_bytes());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let is_initialized = match src[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+
+        Ok(Self {
+            is_initialized,
+            initializer_pubkey: Pubkey::try_from(&src[1..33]).map_err(|_| ProgramError::InvalidAccountData)?,
+            vault_account_pubkey: Pubkey::try_from(&src[33..65]).map_err(|_| ProgramError::InvalidAccountData)?,
+            amount: u64::from_le_bytes(src[65..73].try_into().map_err(|_| ProgramError::InvalidAccountData)?),
+        })
+    }
+}
+
+impl CryptoTracker {
+    /// State of `data_account`, which has to be an initialized account of `program_id`
+    pub fn from_account(account: &Account, data_account: &Pubkey, program_id: &Pubkey) -> Result<Self, VentureLaunchError> {
+        if account.owner != *program_id {
+            return Err(VentureLaunchError::WrongDataAccountOwner { data_account: *data_account, owner: account.owner });
+        }
+
+        let state = Self::unpack_unchecked(&account.data).map_err(|_| VentureLaunchError::InvalidDataAccount(*data_account))?;
+        if !state.is_initialized() {
+            return Err(VentureLaunchError::DataAccountNotInitialized(*data_account));
+        }
+
+        Ok(state)
+    }
+}
+
+/// What a vault holds, `authority` is the initializer the program lets withdraw
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VaultView {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(state: &CryptoTracker, owner: Pubkey) -> Account {
+        let mut data = vec![0; CryptoTracker::LEN];
+        state.pack_into_slice(&mut data);
+
+        Account { lamports: 1, data, owner, executable: false, rent_epoch: 0 }
+    }
+
+    fn state() -> CryptoTracker {
+        CryptoTracker {
+            is_initialized: true,
+            initializer_pubkey: Pubkey::new_unique(),
+            vault_account_pubkey: Pubkey::new_unique(),
+            amount: 1_000_000,
+        }
+    }
+
+    #[test]
+    fn state_round_trips() {
+        let state = state();
+        let mut data = vec![0; CryptoTracker::LEN];
+        CryptoTracker::pack(state, &mut data).unwrap();
+
+        assert_eq!(73, CryptoTracker::LEN);
+        assert_eq!(1, data[0]);
+        assert_eq!(&1_000_000u64.to_le_bytes(), &data[65..73]);
+        assert_eq!(state, CryptoTracker::unpack(&data).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        let mut data = vec![0; CryptoTracker::LEN];
+        data[0] = 2;
+
+        assert!(CryptoTracker::unpack_unchecked(&data).is_err());
+        assert!(CryptoTracker::unpack_unchecked(&data[..72]).is_err());
+    }
+
+    #[test]
+    fn checks_owner_and_initialized_flag() {
+        let program_id = Pubkey::new_unique();
+        let data_account = Pubkey::new_unique();
+        let state = state();
+
+        assert_eq!(state, CryptoTracker::from_account(&account(&state, program_id), &data_account, &program_id).unwrap());
+        assert!(matches!(
+            CryptoTracker::from_account(&account(&state, Pubkey::new_unique()), &data_account, &program_id),
+            Err(VentureLaunchError::WrongDataAccountOwner { .. })
+        ));
+        assert!(matches!(
+            CryptoTracker::from_account(&account(&CryptoTracker::default(), program_id), &data_account, &program_id),
+            Err(VentureLaunchError::DataAccountNotInitialized(_))
+        ));
+    }
+}
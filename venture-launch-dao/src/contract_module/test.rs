@@ -0,0 +1,83 @@
+#![cfg(test)]
+
+use solana_sdk::{
+    pubkey::Pubkey, signature::{Keypair, Signer}, signer::EncodableKey, transaction::Transaction
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use std::str::FromStr;
+use dotenv::dotenv;
+
+use crate::contract_module::{
+    venture_launch::VentureLaunch,
+    associated_token,
+};
+
+async fn send(vl: &VentureLaunch, mut tx: Transaction, signers: &[&Keypair]) -> solana_sdk::signature::Signature {
+    let recent_blockhash = vl.rpc_client.get_latest_blockhash().await.unwrap();
+    tx.sign(signers, recent_blockhash);
+
+    vl.rpc_client.send_and_confirm_transaction(&tx).await.unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs a local validator at localhost:8899 with the program deployed and KEYPAIR_PATH"]
+async fn contract_module_test() {
+    dotenv().ok();
+    let native_mint = spl_token::native_mint::id();
+
+    let mut vl = VentureLaunch::new(
+        RpcClient::new("http://localhost:8899".to_string()),
+        Pubkey::from_str("B1Lmegd5rBAAZ4nBRN9ePeMcThLdEQ5ec3yfDZZJxnBY").unwrap(),
+        Pubkey::from_str("8wd6uprrkJfgZdfu8PaAX6N1ZVJJStAtebdzgpDevJij").unwrap(),
+        Pubkey::from_str("ApkXb5ayRzpxnH8yaVLgzEbLD4jhoGVzEWw5HTML9tcv").unwrap(),
+        native_mint
+    );
+
+    // Initialize a keypair for the payer
+    let payer = Keypair::read_from_file(std::env::var("KEYPAIR_PATH").expect("KEYPAIR_PATH must be set.")).unwrap();
+    println!("Payer: {:?}", payer.pubkey());
+
+    // Contract initialization transaction
+    println!("Invoking create_vault...");
+    let vault_account = Keypair::new();
+    let data_account = Keypair::new();
+    let tx = vl.transaction_create_vault(&payer.pubkey(), &vault_account, &data_account).await.unwrap();
+    let signature = send(&vl, tx, &[&payer, &vault_account, &data_account]).await;
+    println!("vault_account: {}", vl.vault_account);
+    println!("data_account: {}", vl.data_account);
+
+    println!("[create_vault] Signature: {:?}", signature);
+
+    // Wrap SOL for the deposit
+    let instructions = associated_token::native::instructions_wrap_sol(&vl.rpc_client, &payer.pubkey(), &payer.pubkey(), 2 * 10_u64.pow(9))
+        .await
+        .unwrap();
+    let signature = send(&vl, Transaction::new_with_payer(&instructions, Some(&payer.pubkey())), &[&payer]).await;
+
+    println!("[wrap] Signature: {:?}", signature);
+
+    // Deposit to the vault
+    println!("Invoking deposit...");
+    let tx = vl.transaction_deposit(
+        &payer.pubkey(),
+        &associated_token::utils::get_associated_token_address(&native_mint, &payer.pubkey()),
+        2 * 10_u64.pow(9)
+    );
+    let signature = send(&vl, tx, &[&payer]).await;
+
+    println!("[deposit] Signature: {:?}", signature);
+
+    // Withdraw from the vault
+    println!("Invoking withdraw...");
+    let tx = vl.transaction_withdraw(
+        &payer.pubkey(),
+        &payer.pubkey(),
+        10_u64.pow(9)
+    );
+    let signature = send(&vl, tx, &[&payer]).await;
+
+    println!("[withdraw] Signature: {:?}", signature);
+
+    // Check account data
+    println!("Vault balance: {}", vl.get_vault_balance().await.unwrap());
+}
@@ -0,0 +1,171 @@
+use std::str::FromStr;
+
+use dotenv::dotenv;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    program_pack::Pack, pubkey::Pubkey, signature::{Keypair, Signer}, transaction::Transaction
+};
+use spl_token::state::Account as TokenAccount;
+
+use super::associated_token::{instruction::create_associated_token_program_idempotent_instruction_for_owner, utils::get_associated_token_address};
+use super::error::VentureLaunchError;
+use super::instruction;
+use super::state::{CryptoTracker, VaultView};
+
+/// `VENTURE_LAUNCH_PROGRAM_ID`, or the program deployed for the platform
+pub fn get_program_id() -> Pubkey {
+    dotenv().ok();
+
+    std::env::var("VENTURE_LAUNCH_PROGRAM_ID")
+        .ok()
+        .and_then(|program_id| Pubkey::from_str(&program_id).ok())
+        .unwrap_or_else(|| Pubkey::from_str("B1Lmegd5rBAAZ4nBRN9ePeMcThLdEQ5ec3yfDZZJxnBY").unwrap())
+}
+
+/// Client of the escrow program. A vault is a token account owned by the program together with
+/// the data account tracking how much was deposited to it
+pub struct VentureLaunch {
+    pub rpc_client: RpcClient,
+    pub program_id: Pubkey,
+    pub vault_account: Pubkey,
+    pub data_account: Pubkey,
+    pub mint: Pubkey,
+}
+
+impl VentureLaunch {
+    pub fn new(
+        rpc_client: RpcClient,
+        program_id: Pubkey,
+        vault_account: Pubkey,
+        data_account: Pubkey,
+        mint: Pubkey,
+    ) -> VentureLaunch {
+        VentureLaunch {
+            rpc_client,
+            program_id,
+            vault_account,
+            data_account,
+            mint,
+        }
+    }
+
+    /// Client of an existing vault, the mint is read from the vault token account
+    pub async fn from_vault(
+        rpc_client: RpcClient,
+        program_id: Pubkey,
+        vault_account: Pubkey,
+        data_account: Pubkey,
+    ) -> Result<VentureLaunch, VentureLaunchError> {
+        let raw_data = rpc_client
+            .get_account_data(&vault_account)
+            .await
+            .map_err(|_| VentureLaunchError::FailedToFetchAccount(vault_account))?;
+        let vault = TokenAccount::unpack(&raw_data).map_err(|_| VentureLaunchError::InvalidVaultAccount(vault_account))?;
+
+        Ok(Self::new(rpc_client, program_id, vault_account, data_account, vault.mint))
+    }
+
+    /// Creates the vault token account and the data account and hands both to the program.
+    /// Both new accounts have to sign next to `payer`
+    pub async fn transaction_create_vault(
+        &mut self,
+        payer: &Pubkey,
+        vault_account: &Keypair,
+        data_account: &Keypair,
+    ) -> Result<Transaction, VentureLaunchError> {
+        self.vault_account = vault_account.pubkey();
+        self.data_account = data_account.pubkey();
+
+        let vault_rent = self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(TokenAccount::LEN)
+            .await
+            .map_err(|_| VentureLaunchError::FailedToFetchRent)?;
+        let data_rent = self
+            .rpc_client
+            .get_minimum_balance_for_rent_exemption(CryptoTracker::LEN)
+            .await
+            .map_err(|_| VentureLaunchError::FailedToFetchRent)?;
+
+        let instructions = vec![
+            // Token account that will be transfered to program
+            solana_sdk::system_instruction::create_account(
+                payer,
+                &self.vault_account,
+                vault_rent,
+                TokenAccount::LEN as u64,
+                &spl_token::id()
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &self.vault_account,
+                &self.mint,
+                payer
+            ).map_err(|err| VentureLaunchError::FailedToBuildInstruction(err.to_string()))?,
+            // Data account (to store state) that will be transfered to program
+            solana_sdk::system_instruction::create_account(
+                payer,
+                &self.data_account,
+                data_rent,
+                CryptoTracker::LEN as u64,
+                &self.program_id
+            ),
+            instruction::create_vault(self, payer),
+        ];
+
+        Ok(Transaction::new_with_payer(&instructions, Some(payer)))
+    }
+
+    /// Moves `amount` from `deposit_account`, a token account of `payer`, to the vault
+    pub fn transaction_deposit(
+        &self,
+        payer: &Pubkey,
+        deposit_account: &Pubkey,
+        amount: u64
+    ) -> Transaction {
+        let deposit_instruction = instruction::deposit(self, payer, deposit_account, amount);
+
+        Transaction::new_with_payer(&[deposit_instruction], Some(payer))
+    }
+
+    /// Moves `amount` from the vault to the token account of `receiver` for the vault mint.
+    /// The token account is created first when missing, paid by `payer`
+    pub fn transaction_withdraw(
+        &self,
+        payer: &Pubkey,
+        receiver: &Pubkey,
+        amount: u64,
+    ) -> Transaction {
+        let withdraw_account = get_associated_token_address(&self.mint, receiver);
+        let instructions = [
+            create_associated_token_program_idempotent_instruction_for_owner(payer, &withdraw_account, receiver, &self.mint),
+            instruction::withdraw(self, payer, &withdraw_account, amount),
+        ];
+
+        Transaction::new_with_payer(&instructions, Some(payer))
+    }
+
+    /// State of the data account, checked to be the program's and to track this vault
+    pub async fn get_vault(&self) -> Result<VaultView, VentureLaunchError> {
+        let account = self
+            .rpc_client
+            .get_account(&self.data_account)
+            .await
+            .map_err(|_| VentureLaunchError::FailedToFetchAccount(self.data_account))?;
+        let state = CryptoTracker::from_account(&account, &self.data_account, &self.program_id)?;
+        if state.vault_account_pubkey != self.vault_account {
+            return Err(VentureLaunchError::VaultMismatch { data_account: self.data_account, vault_account: state.vault_account_pubkey });
+        }
+
+        Ok(VaultView {
+            mint: self.mint,
+            vault: state.vault_account_pubkey,
+            authority: state.initializer_pubkey,
+            amount: state.amount,
+        })
+    }
+
+    pub async fn get_vault_balance(&self) -> Result<u64, VentureLaunchError> {
+        Ok(self.get_vault().await?.amount)
+    }
+}
@@ -0,0 +1,127 @@
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Marks memos written by the platform among other squads memos, the number is the version of the format
+pub const AUDIT_MEMO_PREFIX: &str = "vld1:";
+
+/// Platform ids of the request a transaction is built for
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditContext {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+    /// Milestone or proposal id of the platform, not the squads transaction index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposal_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<i64>,
+    /// Platform user the request was made by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+}
+
+impl AuditContext {
+    /// Ids of a broker request, read from the optional `project_id`, `proposal_id` or `milestone_id`
+    /// and `operator` fields every command accepts. Ids may be JSON strings or numbers
+    pub fn from_request(request_id: Option<i64>, payload: &str) -> Self {
+        let payload: Value = serde_json::from_str(payload).unwrap_or(Value::Null);
+        let field = |name: &str| match payload.get(name) {
+            Some(Value::String(value)) => Some(value.clone()),
+            Some(Value::Number(value)) => Some(value.to_string()),
+            _ => None
+        };
+
+        Self {
+            project_id: field("project_id"),
+            proposal_id: field("proposal_id").or_else(|| field("milestone_id")),
+            request_id,
+            operator: field("operator"),
+        }
+    }
+}
+
+tokio::task_local! {
+    /// Ids of the broker request being handled, every memo built while handling it carries them
+    static AUDIT_CONTEXT: AuditContext;
+}
+
+/// Runs `future` with `context` in the memo of every transaction built inside it
+pub async fn with_audit_context<F: Future>(context: AuditContext, future: F) -> F::Output {
+    AUDIT_CONTEXT.scope(context, future).await
+}
+
+pub fn current_audit_context() -> AuditContext {
+    AUDIT_CONTEXT.try_with(|context| context.clone()).unwrap_or_default()
+}
+
+/// Memo of a squads transaction or vote: the platform ids and what the transaction does
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AuditMemo {
+    #[serde(flatten)]
+    pub context: AuditContext,
+    pub action: String,
+}
+
+impl AuditMemo {
+    /// Memo of `action` for the request being handled
+    pub fn new(action: impl Into<String>) -> Self {
+        Self { context: current_audit_context(), action: action.into() }
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{AUDIT_MEMO_PREFIX}{}", serde_json::to_string(self).expect("audit memo serializes into json"))
+    }
+
+    /// `None` for memos not written by the platform
+    pub fn decode(memo: &str) -> Option<Self> {
+        serde_json::from_str(memo.strip_prefix(AUDIT_MEMO_PREFIX)?).ok()
+    }
+}
+
+/// Encoded memo of `action` for the request being handled, as squads instruction arguments take it
+pub fn audit_memo(action: impl Into<String>) -> Option<String> {
+    Some(AuditMemo::new(action).encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_ids_of_request() {
+        let context = AuditContext::from_request(
+            Some(12),
+            r#"{"multisig_pda": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin", "project_id": 7, "milestone_id": "42", "operator": "alice"}"#,
+        );
+
+        assert_eq!(
+            AuditContext {
+                project_id: Some("7".to_string()),
+                proposal_id: Some("42".to_string()),
+                request_id: Some(12),
+                operator: Some("alice".to_string()),
+            },
+            context
+        );
+        assert_eq!(AuditContext::default(), AuditContext::from_request(None, "not json"));
+    }
+
+    #[tokio::test]
+    async fn memo_carries_context_of_request() {
+        let context = AuditContext { project_id: Some("7".to_string()), request_id: Some(12), ..AuditContext::default() };
+
+        let memo = with_audit_context(context.clone(), async { AuditMemo::new("Add member") }).await;
+        let encoded = memo.encode();
+
+        assert_eq!(r#"vld1:{"project_id":"7","request_id":12,"action":"Add member"}"#, encoded);
+        assert_eq!(Some(AuditMemo { context, action: "Add member".to_string() }), AuditMemo::decode(&encoded));
+        assert_eq!(AuditContext::default(), AuditMemo::new("Add member").context);
+    }
+
+    #[test]
+    fn other_memos_are_not_decoded() {
+        assert_eq!(None, AuditMemo::decode("Deploy my own Squad"));
+        assert_eq!(None, AuditMemo::decode("vld1:not json"));
+    }
+}
@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use super::error::MilestoneError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneStatus {
+    /// Nothing was withdrawn for the milestone yet
+    Pending,
+    /// A vault transaction paying the milestone is created and approved but not executed
+    Proposed,
+    /// The vault transaction paying the milestone was executed
+    Released,
+}
+
+impl MilestoneStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Proposed => "proposed",
+            Self::Released => "released",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pending" => Some(Self::Pending),
+            "proposed" => Some(Self::Proposed),
+            "released" => Some(Self::Released),
+            _ => None
+        }
+    }
+}
+
+/// Tranche of the vault budget the backend releases once the milestone with the same `id` is reached.
+/// `id` is the `proposal_id` the backend sends with `withdraw`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Milestone {
+    pub id: String,
+    /// Budget of the milestone in vault token units
+    pub amount: u64,
+    #[serde(with = "pubkey_string")]
+    pub recipient: Pubkey,
+    pub status: MilestoneStatus,
+    /// Index of the squads vault transaction paying the milestone, once proposed
+    pub transaction_index: Option<u64>,
+    /// Amount of the proposed or released withdraw
+    pub withdrawn: u64,
+}
+
+impl Milestone {
+    pub fn new(id: String, amount: u64, recipient: Pubkey) -> Result<Self, MilestoneError> {
+        if amount == 0 {
+            return Err(MilestoneError::ZeroAmount);
+        }
+
+        Ok(Self {
+            id,
+            amount,
+            recipient,
+            status: MilestoneStatus::Pending,
+            transaction_index: None,
+            withdrawn: 0,
+        })
+    }
+
+    /// Checks that a withdraw of `amount` to `receiver` may be proposed for the milestone
+    pub fn check_propose(&self, receiver: &Pubkey, amount: u64) -> Result<(), MilestoneError> {
+        match (self.status, self.transaction_index) {
+            (MilestoneStatus::Released, _) => return Err(MilestoneError::AlreadyReleased(self.id.clone())),
+            (MilestoneStatus::Proposed, Some(transaction_index)) => {
+                return Err(MilestoneError::AlreadyProposed { id: self.id.clone(), transaction_index })
+            },
+            _ => {}
+        }
+
+        self.check_withdraw(receiver, amount)
+    }
+
+    pub fn propose(&mut self, receiver: &Pubkey, amount: u64, transaction_index: u64) -> Result<(), MilestoneError> {
+        self.check_propose(receiver, amount)?;
+
+        self.status = MilestoneStatus::Proposed;
+        self.transaction_index = Some(transaction_index);
+        self.withdrawn = amount;
+
+        Ok(())
+    }
+
+    /// Checks that the proposed withdraw matches `receiver` and `amount`, returns the vault transaction to execute
+    pub fn check_release(&self, receiver: &Pubkey, amount: u64) -> Result<u64, MilestoneError> {
+        let transaction_index = match (self.status, self.transaction_index) {
+            (MilestoneStatus::Released, _) => return Err(MilestoneError::AlreadyReleased(self.id.clone())),
+            (MilestoneStatus::Proposed, Some(transaction_index)) => transaction_index,
+            _ => return Err(MilestoneError::NotProposed(self.id.clone()))
+        };
+
+        self.check_withdraw(receiver, amount)?;
+        if amount != self.withdrawn {
+            return Err(MilestoneError::AmountMismatch { id: self.id.clone(), proposed: self.withdrawn, amount });
+        }
+
+        Ok(transaction_index)
+    }
+
+    pub fn release(&mut self, receiver: &Pubkey, amount: u64) -> Result<(), MilestoneError> {
+        self.check_release(receiver, amount)?;
+
+        self.status = MilestoneStatus::Released;
+
+        Ok(())
+    }
+
+    fn check_withdraw(&self, receiver: &Pubkey, amount: u64) -> Result<(), MilestoneError> {
+        if *receiver != self.recipient {
+            return Err(MilestoneError::RecipientMismatch { id: self.id.clone(), recipient: self.recipient, receiver: *receiver });
+        }
+        if amount > self.amount {
+            return Err(MilestoneError::ExceedsBudget { id: self.id.clone(), budget: self.amount, amount });
+        }
+
+        Ok(())
+    }
+}
+
+/// Serializes pubkeys as base58 strings so milestones in answers stay readable
+mod pubkey_string {
+    use std::str::FromStr;
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn serialize<S: Serializer>(pubkey: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&pubkey.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        Pubkey::from_str(&value).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milestone() -> Milestone {
+        Milestone::new("1".to_string(), 100, Pubkey::new_unique()).unwrap()
+    }
+
+    #[test]
+    fn milestone_is_released_once() {
+        let mut milestone = milestone();
+        let recipient = milestone.recipient;
+
+        milestone.propose(&recipient, 80, 5).unwrap();
+        assert_eq!(Some(5), milestone.transaction_index);
+        assert!(matches!(milestone.check_propose(&recipient, 80), Err(MilestoneError::AlreadyProposed { transaction_index: 5, .. })));
+
+        assert_eq!(5, milestone.check_release(&recipient, 80).unwrap());
+        milestone.release(&recipient, 80).unwrap();
+        assert_eq!(MilestoneStatus::Released, milestone.status);
+
+        assert!(matches!(milestone.check_propose(&recipient, 80), Err(MilestoneError::AlreadyReleased(_))));
+        assert!(matches!(milestone.release(&recipient, 80), Err(MilestoneError::AlreadyReleased(_))));
+    }
+
+    #[test]
+    fn withdraw_is_limited_by_budget_and_recipient() {
+        let milestone = milestone();
+
+        assert!(matches!(milestone.check_propose(&milestone.recipient, 101), Err(MilestoneError::ExceedsBudget { budget: 100, .. })));
+        assert!(matches!(milestone.check_propose(&Pubkey::new_unique(), 10), Err(MilestoneError::RecipientMismatch { .. })));
+        assert!(matches!(Milestone::new("2".to_string(), 0, Pubkey::new_unique()), Err(MilestoneError::ZeroAmount)));
+    }
+
+    #[test]
+    fn release_has_to_match_proposal() {
+        let mut milestone = milestone();
+        let recipient = milestone.recipient;
+
+        assert!(matches!(milestone.check_release(&recipient, 50), Err(MilestoneError::NotProposed(_))));
+
+        milestone.propose(&recipient, 50, 7).unwrap();
+        assert!(matches!(milestone.check_release(&recipient, 60), Err(MilestoneError::AmountMismatch { proposed: 50, .. })));
+    }
+
+    #[test]
+    fn milestone_round_trips_through_json() {
+        let milestone = milestone();
+
+        let json = serde_json::to_string(&milestone).unwrap();
+
+        assert!(json.contains(&milestone.recipient.to_string()));
+        assert!(json.contains("\"pending\""));
+        assert_eq!(milestone, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn status_names_match_json() {
+        for status in [MilestoneStatus::Pending, MilestoneStatus::Proposed, MilestoneStatus::Released] {
+            assert_eq!(format!("\"{}\"", status.name()), serde_json::to_string(&status).unwrap());
+            assert_eq!(Some(status), MilestoneStatus::from_name(status.name()));
+        }
+        assert_eq!(None, MilestoneStatus::from_name("unknown"));
+    }
+}
@@ -0,0 +1,30 @@
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone)]
+pub enum MilestoneError {
+    #[error("Milestone {0} does not exist")]
+    UnknownMilestone(String),
+    #[error("Milestone {0} already exists")]
+    DuplicateMilestone(String),
+    #[error("Milestone amount must be greater than zero")]
+    ZeroAmount,
+    #[error("Milestone {0} is already released")]
+    AlreadyReleased(String),
+    #[error("Milestone {id} is already proposed in vault transaction {transaction_index}")]
+    AlreadyProposed { id: String, transaction_index: u64 },
+    #[error("Milestone {0} has no proposed withdraw to execute")]
+    NotProposed(String),
+    #[error("Milestone {id} pays {recipient}, not {receiver}")]
+    RecipientMismatch { id: String, recipient: Pubkey, receiver: Pubkey },
+    #[error("Milestone {id} has a budget of {budget} but {amount} were requested")]
+    ExceedsBudget { id: String, budget: u64, amount: u64 },
+    #[error("Milestone {id} was proposed for {proposed} but {amount} were requested")]
+    AmountMismatch { id: String, proposed: u64, amount: u64 },
+    #[error("Milestone {id} is paid in the vault token mint, not {mint}")]
+    MintMismatch { id: String, mint: Pubkey },
+    #[error("Failed to read milestones from the store: {0}")]
+    FailedToReadStore(String),
+    #[error("Failed to write milestones to the store: {0}")]
+    FailedToWriteStore(String),
+}
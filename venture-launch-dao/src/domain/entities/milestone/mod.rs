@@ -0,0 +1,2 @@
+pub mod entity;
+pub mod error;
\ No newline at end of file
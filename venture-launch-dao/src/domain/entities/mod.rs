@@ -1 +1,4 @@
-pub mod multisig;
\ No newline at end of file
+pub mod multisig;
+pub mod memo;
+pub mod milestone;
+pub mod token;
\ No newline at end of file
@@ -1,14 +1,19 @@
-use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::{
+    nonblocking::rpc_client::RpcClient, rpc_config::RpcProgramAccountsConfig, rpc_filter::{Memcmp, RpcFilterType}
+};
 use solana_sdk::{
     instruction::Instruction, message::Message, pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction
 };
 use squads_multisig::{
-    anchor_lang::AccountDeserialize, client::{proposal_approve, proposal_cancel, ProposalVoteAccounts, ProposalVoteArgs}, pda::{get_multisig_pda, get_program_config_pda, get_proposal_pda, get_vault_pda}, squads_multisig_program::{self, state::ProgramConfig, Multisig}, state::{
+    anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas}, client::{proposal_approve, proposal_cancel, ProposalVoteAccounts, ProposalVoteArgs}, pda::{get_multisig_pda, get_program_config_pda, get_proposal_pda, get_vault_pda}, squads_multisig_program::{self, state::ProgramConfig, Multisig, SpendingLimit}, state::{
         Member, Proposal, ProposalStatus
     }
 };
 use async_trait::async_trait;
 
+use crate::domain::entities::memo::audit_memo::audit_memo;
+use crate::domain::services::balance_service::{get_balances, Balances};
+
 use super::{base_multisig::{BaseMultisig, BaseMultisigCreateArgs, BaseMultisigInitArgs}, error::BaseMultisigError};
 
 #[async_trait]
@@ -38,6 +43,8 @@ pub trait BaseMultisigTrait<Args>: Send + Sync {
         Ok(multisig.is_member(member_pubkey).is_some())
     }
     async fn get_current_proposal_status(&self)       -> Result<ProposalStatus,  Self::Error>;
+    async fn get_spending_limits(&self)               -> Result<Vec<(Pubkey, SpendingLimit)>, Self::Error>;
+    async fn get_vault_balances(&self)                -> Result<Balances,        Self::Error>;
 
     async fn get_transaction_from_instructions(&self, sender: Pubkey, instructions: &[Instruction]) -> Result<Transaction, Self::Error>;
 
@@ -50,7 +57,9 @@ pub trait BaseMultisigTrait<Args>: Send + Sync {
     fn get_create_keypair(&self) -> &Option<Keypair>;
 
     async fn instruction_proposal_approve(&self, approver: Pubkey)  -> Result<Instruction, Self::Error>;
+    async fn instruction_proposal_approve_for_index(&self, approver: Pubkey, transaction_index: u64) -> Result<Instruction, Self::Error>;
     async fn instruction_proposal_cancel(&self, canceler: Pubkey) -> Result<Instruction, Self::Error>;
+    async fn instruction_proposal_reject(&self, rejecter: Pubkey) -> Result<Instruction, Self::Error>;
     async fn transaction_proposal_approve(&self, approver: Pubkey)  -> Result<Transaction, Self::Error> {
         let ix = self.instruction_proposal_approve(approver).await?;
 
@@ -63,6 +72,12 @@ pub trait BaseMultisigTrait<Args>: Send + Sync {
 
         Ok(self.get_transaction_from_instructions(canceler, &[ix]).await?)
     }
+
+    async fn transaction_proposal_reject(&self, rejecter: Pubkey) -> Result<Transaction, Self::Error> {
+        let ix = self.instruction_proposal_reject(rejecter).await?;
+
+        Ok(self.get_transaction_from_instructions(rejecter, &[ix]).await?)
+    }
 }
 
 #[async_trait]
@@ -194,6 +209,42 @@ impl BaseMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
         Ok(proposal.status)
     }
 
+    async fn get_spending_limits(&self) -> Result<Vec<(Pubkey, SpendingLimit)>, Self::Error> {
+        let program_id = squads_multisig_program::ID;
+
+        // Every squads account keeps the multisig key right after the anchor discriminator,
+        // accounts that are not spending limits are dropped by the discriminator check below
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, self.multisig_pda.as_ref()))]),
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts =
+        match self.rpc_client.get_program_accounts_with_config(&program_id, config).await {
+            Ok(accounts) => accounts,
+            Err(_) => return Err(Self::Error::FailedToFetchSpendingLimitAccounts)
+        };
+
+        let spending_limits = accounts
+            .into_iter()
+            .filter_map(|(pubkey, account)| {
+                let mut spending_limit_data = account.data.as_slice();
+                SpendingLimit::try_deserialize(&mut spending_limit_data)
+                    .ok()
+                    .map(|spending_limit| (pubkey, spending_limit))
+            })
+            .collect();
+
+        Ok(spending_limits)
+    }
+
+    async fn get_vault_balances(&self) -> Result<Balances, Self::Error> {
+        match get_balances(&self.rpc_client, &self.vault_pda).await {
+            Ok(balances) => Ok(balances),
+            Err(_) => Err(Self::Error::FailedToFetchVaultBalances)
+        }
+    }
+
     async fn get_transaction_from_instructions(&self, sender: Pubkey, instructions: &[Instruction]) -> Result<Transaction, Self::Error> {
         let mut message = Message::new(instructions, Some(&sender));
         let recent_blockhash =
@@ -207,8 +258,13 @@ impl BaseMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
     }
 
     async fn instruction_proposal_approve(&self, approver: Pubkey)  -> Result<Instruction, Self::Error> {
-        let program_id: Pubkey = squads_multisig_program::ID;
         let transaction_index = self.get_multisig_transaction_index().await?;
+
+        self.instruction_proposal_approve_for_index(approver, transaction_index).await
+    }
+
+    async fn instruction_proposal_approve_for_index(&self, approver: Pubkey, transaction_index: u64) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
         let (proposal_pda, _) = get_proposal_pda(&self.multisig_pda, transaction_index, Some(&program_id));
 
         let proposal_approve_ix = proposal_approve(
@@ -217,7 +273,7 @@ impl BaseMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
                 member: approver,
                 proposal: proposal_pda
             },
-            ProposalVoteArgs { memo: None },
+            ProposalVoteArgs { memo: audit_memo(format!("Approve transaction {transaction_index}")) },
             Some(program_id)
         );
 
@@ -242,12 +298,31 @@ impl BaseMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
                 member: canceler,
                 proposal: proposal_pda
             },
-            ProposalVoteArgs { memo: None },
+            ProposalVoteArgs { memo: audit_memo(format!("Cancel transaction {transaction_index}")) },
             Some(program_id)
         );
 
         Ok(proposal_cancel_ix)
     }
+    async fn instruction_proposal_reject(&self, rejecter: Pubkey) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let transaction_index = self.get_multisig_transaction_index().await?;
+        let (proposal_pda, _) = get_proposal_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+
+        let proposal_reject_ix = Instruction {
+            program_id,
+            accounts: ProposalVoteAccounts {
+                multisig: self.multisig_pda,
+                member: rejecter,
+                proposal: proposal_pda
+            }.to_account_metas(Some(false)),
+            data: squads_multisig_program::instruction::ProposalReject {
+                args: ProposalVoteArgs { memo: audit_memo(format!("Reject transaction {transaction_index}")) }
+            }.data()
+        };
+
+        Ok(proposal_reject_ix)
+    }
 }
 
 #[cfg(test)]
@@ -259,6 +334,7 @@ mod tests {
     use tokio;
 
     #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
     async fn get_base_multisig_instance() -> Result<(), Box<dyn Error>> {
         let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
         let creator: Keypair = Keypair::new();
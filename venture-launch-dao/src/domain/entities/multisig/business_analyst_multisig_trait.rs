@@ -1,29 +1,60 @@
-use std::str::FromStr;
-
 use super::{
     base_multisig::{BaseMultisig, BaseMultisigCreateArgs},
     base_multisig_trait::BaseMultisigTrait,
+    config_actions::validate_config_actions,
     error::BaseMultisigError,
 };
+use crate::contract_module::{
+    instruction::{get_pda, instruction_create_vault, instruction_withdraw, CreateVaultAccounts, WithdrawAccounts},
+    state::CryptoTracker,
+};
+use crate::domain::entities::memo::audit_memo::audit_memo;
+use crate::domain::entities::token::{amount::format_ui_amount, error::TokenError, mint::MintInfo};
 use async_trait::async_trait;
 use solana_sdk::{
-    instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer, system_program, transaction::Transaction
+    hash::hash, instruction::{AccountMeta, Instruction}, message::Message, packet::PACKET_DATA_SIZE, program_pack::Pack, pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction, system_program, transaction::Transaction
 };
-use spl_associated_token_account::get_associated_token_address_with_program_id;
 use squads_multisig::{
+    anchor_lang::{AccountDeserialize, AnchorSerialize, InstructionData, ToAccountMetas},
     client::{
         self, config_transaction_create, config_transaction_execute, multisig_create_v2,
-        proposal_create, vault_transaction_create, vault_transaction_execute,
+        proposal_create, spending_limit_use, vault_transaction_create, vault_transaction_execute,
         ConfigTransactionCreateAccounts, ConfigTransactionCreateArgs,
         ConfigTransactionExecuteAccounts, MultisigCreateAccountsV2, MultisigCreateArgsV2,
-        ProposalCreateArgs, VaultTransactionCreateAccounts, VaultTransactionExecuteAccounts,
+        ProposalCreateArgs, SpendingLimitUseAccounts, VaultTransactionCreateAccounts,
+        VaultTransactionExecuteAccounts,
+    },
+    pda::{get_ephemeral_signer_pda, get_proposal_pda, get_spending_limit_pda, get_transaction_pda},
+    squads_multisig_program::{
+        self, Period, SpendingLimit, SpendingLimitUseArgs, VaultTransactionCreateArgs,
     },
-    pda::{get_proposal_pda, get_transaction_pda},
-    squads_multisig_program,
     state::{ConfigAction, Member, Permission, Permissions, TransactionMessage},
     vault_transaction::VaultTransactionMessageExt,
 };
 
+/// Decimals squads expects for spending limits denominated in SOL
+pub const NATIVE_SOL_DECIMALS: u8 = 9;
+/// Largest vault transaction message squads accepts in a transaction buffer
+pub const MAX_TRANSACTION_BUFFER_SIZE: usize = 4000;
+/// Bytes of the message uploaded by each transaction buffer create or extend
+pub const TRANSACTION_BUFFER_CHUNK_SIZE: usize = 700;
+/// Transaction buffer accounts looked up per `getMultipleAccounts` call, the most the RPC accepts
+const TRANSACTION_BUFFER_LOOKUP_SIZE: usize = 100;
+/// Bytes the compute budget program id and its two instructions add to a transaction
+const COMPUTE_BUDGET_RESERVED_SIZE: usize = 64;
+/// Ephemeral signers of a vault transaction creating a VentureLaunch escrow, its token account and its data account
+pub const VENTURE_LAUNCH_CREATE_VAULT_EPHEMERAL_SIGNERS: u8 = 2;
+
+/// Spending limit added to the multisig, `create_key` seeds its pda, see `get_multisig_spending_limit_pda`
+pub struct AddSpendingLimitArgs {
+    pub create_key: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub period: Period,
+    pub members: Vec<Pubkey>,
+    pub destinations: Vec<Pubkey>,
+}
+
 #[async_trait]
 pub trait BusinessAnalystMultisigTrait<Args = BaseMultisigCreateArgs>:
     BaseMultisigTrait<Args, Error = BaseMultisigError>
@@ -56,6 +87,7 @@ pub trait BusinessAnalystMultisigTrait<Args = BaseMultisigCreateArgs>:
     async fn instruction_transfer_from_vault(
         &self,
         sender: Pubkey,
+        mint_info: &MintInfo,
         receiver: Pubkey,
         lamports: u64,
     ) -> Result<Instruction, Self::Error>;
@@ -63,21 +95,307 @@ pub trait BusinessAnalystMultisigTrait<Args = BaseMultisigCreateArgs>:
         &self,
         creator: Pubkey,
     ) -> Result<Instruction, Self::Error>;
+    async fn instruction_proposal_create_for_index(
+        &self,
+        creator: Pubkey,
+        transaction_index: u64,
+    ) -> Result<Instruction, Self::Error>;
     async fn instruction_config_transaction_execute(
         &self,
         executer: Pubkey,
     ) -> Result<Instruction, Self::Error>;
+    async fn instruction_config_transaction_execute_with_spending_limits(
+        &self,
+        executer: Pubkey,
+        spending_limits: Vec<Pubkey>,
+    ) -> Result<Instruction, Self::Error>;
+    async fn instruction_config_transaction_execute_for_index(
+        &self,
+        executer: Pubkey,
+        transaction_index: u64,
+        spending_limits: Vec<Pubkey>,
+    ) -> Result<Instruction, Self::Error>;
     async fn instruction_vault_transaction_execute(
         &self,
         sender: Pubkey,
+        mint_info: &MintInfo,
         receiver: Pubkey,
         lamports: u64,
     ) -> Result<Instruction, Self::Error>;
+    fn instruction_vault_transaction_execute_for_index(
+        &self,
+        executer: Pubkey,
+        transaction_index: u64,
+        instructions: &[Instruction],
+        ephemeral_signers: u8,
+    ) -> Result<Instruction, Self::Error>;
     async fn instruction_change_threshold(
         &self,
         changer: Pubkey,
         new_threshold: u16,
     ) -> Result<Instruction, Self::Error>;
+    async fn instruction_batch_config(
+        &self,
+        creator: Pubkey,
+        actions: Vec<ConfigAction>,
+    ) -> Result<Instruction, Self::Error>;
+    async fn instruction_add_spending_limit(
+        &self,
+        creator: Pubkey,
+        args: AddSpendingLimitArgs,
+    ) -> Result<Instruction, Self::Error>;
+    async fn instruction_remove_spending_limit(
+        &self,
+        remover: Pubkey,
+        spending_limit: Pubkey,
+    ) -> Result<Instruction, Self::Error>;
+    async fn instruction_spending_limit_use(
+        &self,
+        member: Pubkey,
+        spending_limit: Pubkey,
+        receiver: Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, Self::Error>;
+    fn instruction_transaction_buffer_create(
+        &self,
+        creator: Pubkey,
+        buffer_index: u8,
+        final_buffer_hash: [u8; 32],
+        final_buffer_size: u16,
+        buffer: Vec<u8>,
+    ) -> Instruction;
+    fn instruction_transaction_buffer_extend(
+        &self,
+        creator: Pubkey,
+        buffer_index: u8,
+        buffer: Vec<u8>,
+    ) -> Instruction;
+    fn instruction_transaction_buffer_close(
+        &self,
+        creator: Pubkey,
+        buffer_index: u8,
+    ) -> Instruction;
+    fn instruction_vault_transaction_create_from_buffer(
+        &self,
+        creator: Pubkey,
+        buffer_index: u8,
+        transaction_index: u64,
+        ephemeral_signers: u8,
+        memo: Option<String>,
+    ) -> Result<Instruction, Self::Error>;
+    /// Closes the config transaction at `transaction_index` and its proposal, rent goes to the multisig `rent_collector`
+    fn instruction_config_transaction_accounts_close(
+        &self,
+        rent_collector: Pubkey,
+        transaction_index: u64,
+    ) -> Instruction;
+    /// Closes the vault transaction at `transaction_index` and its proposal, rent goes to the multisig `rent_collector`
+    fn instruction_vault_transaction_accounts_close(
+        &self,
+        rent_collector: Pubkey,
+        transaction_index: u64,
+    ) -> Instruction;
+
+    /// Instructions creating a vault transaction at `transaction_index` that runs `instructions` from the vault,
+    /// grouped by the transaction they have to be sent in. Fits in one `vault_transaction_create` when the
+    /// message is small enough, otherwise the message is uploaded to a transaction buffer first.
+    /// `instructions` may sign with the first `ephemeral_signers` accounts of `get_ephemeral_signer`
+    async fn instructions_vault_transaction_create(
+        &self,
+        creator: Pubkey,
+        transaction_index: u64,
+        instructions: &[Instruction],
+        ephemeral_signers: u8,
+        memo: Option<String>,
+    ) -> Result<Vec<Vec<Instruction>>, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.get_multisig_pda(), transaction_index, Some(&program_id));
+        let vault_index = 0;
+
+        let message = TransactionMessage::try_compile(&self.get_vault_pda(), instructions, &[])
+            .map_err(|_| Self::Error::FailedToCompileVaultTransactionMessage)?;
+        let memo = audit_memo(memo.unwrap_or_else(|| format!("Vault transaction {transaction_index}")));
+
+        let vault_transaction_create_ix = vault_transaction_create(
+            VaultTransactionCreateAccounts {
+                multisig: self.get_multisig_pda(),
+                transaction: transaction_pda,
+                creator,
+                rent_payer: creator,
+                system_program: system_program::id(),
+            },
+            vault_index,
+            ephemeral_signers,
+            &message,
+            memo.clone(),
+            Some(program_id),
+        );
+
+        if fits_in_transaction(&creator, std::slice::from_ref(&vault_transaction_create_ix)) {
+            return Ok(vec![vec![vault_transaction_create_ix]]);
+        }
+
+        let buffer = message
+            .try_to_vec()
+            .map_err(|_| Self::Error::FailedToCompileVaultTransactionMessage)?;
+        if buffer.len() > MAX_TRANSACTION_BUFFER_SIZE {
+            return Err(Self::Error::TransactionBufferTooLarge);
+        }
+
+        let buffer_index = self.get_free_transaction_buffer_index(creator).await?;
+        let final_buffer_hash = hash(&buffer).to_bytes();
+        let mut chunks = buffer.chunks(TRANSACTION_BUFFER_CHUNK_SIZE);
+
+        let mut groups = vec![vec![self.instruction_transaction_buffer_create(
+            creator,
+            buffer_index,
+            final_buffer_hash,
+            buffer.len() as u16,
+            chunks.next().unwrap_or_default().to_vec(),
+        )]];
+        groups.extend(chunks.map(|chunk| {
+            vec![self.instruction_transaction_buffer_extend(creator, buffer_index, chunk.to_vec())]
+        }));
+        // The buffer is closed by squads once the transaction is created from it
+        groups.push(vec![self.instruction_vault_transaction_create_from_buffer(
+            creator,
+            buffer_index,
+            transaction_index,
+            ephemeral_signers,
+            memo,
+        )?]);
+
+        Ok(groups)
+    }
+
+    /// Lowest buffer index of `creator` without a transaction buffer. A buffer left behind by a failed request
+    /// keeps its index until it is closed, so later requests upload to the next free one
+    async fn get_free_transaction_buffer_index(&self, creator: Pubkey) -> Result<u8, Self::Error> {
+        let buffer_indexes: Vec<u8> = (0..=u8::MAX).collect();
+
+        for chunk in buffer_indexes.chunks(TRANSACTION_BUFFER_LOOKUP_SIZE) {
+            let buffers: Vec<Pubkey> = chunk
+                .iter()
+                .map(|buffer_index| get_transaction_buffer_pda(&self.get_multisig_pda(), &creator, *buffer_index))
+                .collect();
+            let accounts = self
+                .get_rpc_client()
+                .get_multiple_accounts(&buffers)
+                .await
+                .map_err(|_| Self::Error::FailedToFetchTransactionBuffers)?;
+
+            if let Some(position) = accounts.iter().position(Option::is_none) {
+                return Ok(chunk[position]);
+            }
+        }
+
+        Err(Self::Error::NoFreeTransactionBufferIndex)
+    }
+
+    /// Account squads signs for with seeds when executing the vault transaction at `transaction_index`,
+    /// used for accounts the transaction creates
+    fn get_ephemeral_signer(&self, transaction_index: u64, ephemeral_signer_index: u8) -> Pubkey {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.get_multisig_pda(), transaction_index, Some(&program_id));
+
+        get_ephemeral_signer_pda(&transaction_pda, ephemeral_signer_index, Some(&program_id)).0
+    }
+
+    /// Instructions of a vault transaction at `transaction_index` creating a VentureLaunch escrow for the mint with
+    /// the vault as initializer, so only the DAO can withdraw from it. The vault pays the rent of the token account
+    /// and the data account, both are ephemeral signers of the transaction, see `VENTURE_LAUNCH_CREATE_VAULT_EPHEMERAL_SIGNERS`.
+    /// The token account belongs to the program owning the mint, Token-2022 accounts get the same extensions as an associated token account
+    async fn instructions_venture_launch_create_vault(
+        &self,
+        program_id: Pubkey,
+        mint_info: &MintInfo,
+        transaction_index: u64,
+    ) -> Result<Vec<Instruction>, Self::Error> {
+        let vault_pda = self.get_vault_pda();
+        let vault_account = self.get_ephemeral_signer(transaction_index, 0);
+        let data_account = self.get_ephemeral_signer(transaction_index, 1);
+
+        let vault_rent = self
+            .get_rpc_client()
+            .get_minimum_balance_for_rent_exemption(mint_info.token_account_len)
+            .await
+            .map_err(|_| Self::Error::FailedToFetchRent)?;
+        let data_rent = self
+            .get_rpc_client()
+            .get_minimum_balance_for_rent_exemption(CryptoTracker::LEN)
+            .await
+            .map_err(|_| Self::Error::FailedToFetchRent)?;
+
+        let mut instructions = vec![
+            system_instruction::create_account(&vault_pda, &vault_account, vault_rent, mint_info.token_account_len as u64, &mint_info.token_program),
+        ];
+        if mint_info.is_token_2022() {
+            // `token_account_len` has room for the immutable owner extension, which has to be set before initializing
+            instructions.push(
+                spl_token_2022::instruction::initialize_immutable_owner(&mint_info.token_program, &vault_account)
+                    .map_err(|_| Self::Error::FailedToBuildVentureLaunchInstruction)?,
+            );
+        }
+        instructions.extend([
+            spl_token_2022::instruction::initialize_account(&mint_info.token_program, &vault_account, &mint_info.mint, &vault_pda)
+                .map_err(|_| Self::Error::FailedToBuildVentureLaunchInstruction)?,
+            system_instruction::create_account(&vault_pda, &data_account, data_rent, CryptoTracker::LEN as u64, &program_id),
+            instruction_create_vault(
+                &program_id,
+                &CreateVaultAccounts { initializer: vault_pda, vault_account, data_account, token_program: mint_info.token_program },
+            ),
+        ]);
+
+        Ok(instructions)
+    }
+
+    /// VentureLaunch withdraw signed by the vault, for escrows created by `instructions_venture_launch_create_vault`
+    fn instruction_venture_launch_withdraw(
+        &self,
+        program_id: Pubkey,
+        vault_account: Pubkey,
+        data_account: Pubkey,
+        mint_info: &MintInfo,
+        receive_account: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        instruction_withdraw(
+            &program_id,
+            &WithdrawAccounts {
+                caller: self.get_vault_pda(),
+                receive_account,
+                vault_account,
+                data_account,
+                token_program: mint_info.token_program,
+                pda_account: get_pda(&program_id).0,
+            },
+            amount,
+        )
+    }
+
+    /// `transfer_checked` of `amount` of the mint from the vault token account to the token account of `receiver`,
+    /// under whichever token program owns the mint, with the extra accounts of a transfer hook resolved
+    async fn instruction_vault_token_transfer(
+        &self,
+        mint_info: &MintInfo,
+        receiver: Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, Self::Error> {
+        let vault_pda = self.get_vault_pda();
+
+        mint_info
+            .instruction_transfer_checked(
+                self.get_rpc_client(),
+                &mint_info.get_token_account(&vault_pda),
+                &mint_info.get_token_account(&receiver),
+                &vault_pda,
+                amount,
+            )
+            .await
+            .map_err(|_| Self::Error::FailedToBuildTransferInstruction)
+    }
 
     async fn transaction_add_member(
         &self,
@@ -104,11 +422,12 @@ pub trait BusinessAnalystMultisigTrait<Args = BaseMultisigCreateArgs>:
     async fn transaction_transfer_from_vault(
         &self,
         sender: Pubkey,
+        mint_info: &MintInfo,
         receiver: Pubkey,
         lamports: u64,
     ) -> Result<Transaction, Self::Error> {
         let ix = self
-            .instruction_transfer_from_vault(sender, receiver, lamports)
+            .instruction_transfer_from_vault(sender, mint_info, receiver, lamports)
             .await?;
 
         Ok(self
@@ -140,11 +459,12 @@ pub trait BusinessAnalystMultisigTrait<Args = BaseMultisigCreateArgs>:
     async fn transaction_vault_transaction_execute(
         &self,
         sender: Pubkey,
+        mint_info: &MintInfo,
         receiver: Pubkey,
         lamports: u64,
     ) -> Result<Transaction, Self::Error> {
         let ix = self
-            .instruction_vault_transaction_execute(sender, receiver, lamports)
+            .instruction_vault_transaction_execute(sender, mint_info, receiver, lamports)
             .await?;
 
         Ok(self
@@ -165,6 +485,64 @@ pub trait BusinessAnalystMultisigTrait<Args = BaseMultisigCreateArgs>:
             .get_transaction_from_instructions(changer, &[ix])
             .await?)
     }
+
+    async fn transaction_batch_config(
+        &self,
+        creator: Pubkey,
+        actions: Vec<ConfigAction>,
+    ) -> Result<Transaction, Self::Error> {
+        let ix = self
+            .instruction_batch_config(creator, actions)
+            .await?;
+
+        Ok(self
+            .get_transaction_from_instructions(creator, &[ix])
+            .await?)
+    }
+
+    async fn transaction_add_spending_limit(
+        &self,
+        creator: Pubkey,
+        args: AddSpendingLimitArgs,
+    ) -> Result<Transaction, Self::Error> {
+        let ix = self
+            .instruction_add_spending_limit(creator, args)
+            .await?;
+
+        Ok(self
+            .get_transaction_from_instructions(creator, &[ix])
+            .await?)
+    }
+
+    async fn transaction_remove_spending_limit(
+        &self,
+        remover: Pubkey,
+        spending_limit: Pubkey,
+    ) -> Result<Transaction, Self::Error> {
+        let ix = self
+            .instruction_remove_spending_limit(remover, spending_limit)
+            .await?;
+
+        Ok(self
+            .get_transaction_from_instructions(remover, &[ix])
+            .await?)
+    }
+
+    async fn transaction_spending_limit_use(
+        &self,
+        member: Pubkey,
+        spending_limit: Pubkey,
+        receiver: Pubkey,
+        amount: u64,
+    ) -> Result<Transaction, Self::Error> {
+        let ix = self
+            .instruction_spending_limit_use(member, spending_limit, receiver, amount)
+            .await?;
+
+        Ok(self
+            .get_transaction_from_instructions(member, &[ix])
+            .await?)
+    }
 }
 
 #[async_trait]
@@ -218,8 +596,9 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
                 threshold,
                 time_lock,
                 config_authority: None,
-                rent_collector: None,
-                memo: Some("Deploy my own Squad".to_string()),
+                // The creator pays rent for every transaction and proposal, closing them pays it back
+                rent_collector: Some(self.creator),
+                memo: audit_memo("Deploy my own Squad"),
             },
             Some(squads_multisig_program::ID),
         )
@@ -244,7 +623,7 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
                 system_program: system_program::ID,
             },
             ConfigTransactionCreateArgs {
-                memo: Some(format!(
+                memo: audit_memo(format!(
                     "Add {} as member to multisig {}",
                     new_member.key.to_string(),
                     self.multisig_pda
@@ -278,7 +657,7 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
                 system_program: system_program::ID,
             },
             ConfigTransactionCreateArgs {
-                memo: Some(format!(
+                memo: audit_memo(format!(
                     "Remove {} member from multisig {}",
                     old_member_pubkey.to_string(),
                     self.multisig_pda
@@ -296,6 +675,7 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
     async fn instruction_transfer_from_vault(
         &self,
         sender: Pubkey,
+        mint_info: &MintInfo,
         receiver: Pubkey,
         lamports: u64,
     ) -> Result<Instruction, Self::Error> {
@@ -305,19 +685,7 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
             get_transaction_pda(&self.multisig_pda, transaction_index, Some(&program_id));
         let vault_index = 0;
 
-        let token_program_id = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
-        let token_mint = Pubkey::from_str("Gh9ZwEmdLJ8DscKNTkTqPbNwLNNBjuSzaG9Vp2KGtKJr").unwrap();
-
-        let source_pubkey = get_associated_token_address_with_program_id(&self.vault_pda, &token_mint, &token_program_id);
-        let destination_pubkey = get_associated_token_address_with_program_id(&receiver, &token_mint, &token_program_id);
-
-        let ix = spl_token::instruction::transfer(
-            &token_program_id,
-            &source_pubkey,
-            &destination_pubkey,
-            &self.vault_pda,
-            &[&self.vault_pda],
-            lamports).unwrap();
+        let ix = self.instruction_vault_token_transfer(mint_info, receiver, lamports).await?;
 
         let message = TransactionMessage::try_compile(
             &self.vault_pda,
@@ -337,8 +705,10 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
             vault_index,
             0,
             &message,
-            Some(format!(
-                "Sending {lamports} lamports from {} to {}",
+            audit_memo(format!(
+                "Sending {} of {} ({lamports} base units) from {} to {}",
+                format_ui_amount(lamports, mint_info.decimals),
+                mint_info.mint,
                 self.vault_pda.to_string(),
                 receiver.to_string()
             )),
@@ -352,8 +722,18 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
         &self,
         creator: Pubkey,
     ) -> Result<Instruction, Self::Error> {
-        let program_id: Pubkey = squads_multisig_program::ID;
         let transaction_index = self.get_multisig_transaction_index().await?;
+
+        self.instruction_proposal_create_for_index(creator, transaction_index)
+            .await
+    }
+
+    async fn instruction_proposal_create_for_index(
+        &self,
+        creator: Pubkey,
+        transaction_index: u64,
+    ) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
         let (proposal_pda, _) =
             get_proposal_pda(&self.multisig_pda, transaction_index, Some(&program_id));
 
@@ -379,8 +759,28 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
         &self,
         executer: Pubkey,
     ) -> Result<Instruction, Self::Error> {
-        let program_id: Pubkey = squads_multisig_program::ID;
+        self.instruction_config_transaction_execute_with_spending_limits(executer, vec![])
+            .await
+    }
+
+    async fn instruction_config_transaction_execute_with_spending_limits(
+        &self,
+        executer: Pubkey,
+        spending_limits: Vec<Pubkey>,
+    ) -> Result<Instruction, Self::Error> {
         let transaction_index = self.get_multisig_transaction_index().await?;
+
+        self.instruction_config_transaction_execute_for_index(executer, transaction_index, spending_limits)
+            .await
+    }
+
+    async fn instruction_config_transaction_execute_for_index(
+        &self,
+        executer: Pubkey,
+        transaction_index: u64,
+        spending_limits: Vec<Pubkey>,
+    ) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
         let (proposal_pda, _) =
             get_proposal_pda(&self.multisig_pda, transaction_index, Some(&program_id));
         let (transaction_pda, _) =
@@ -395,7 +795,7 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
                 rent_payer: Some(executer),
                 system_program: Some(system_program::ID),
             },
-            vec![],
+            spending_limits,
             Some(program_id),
         );
 
@@ -405,6 +805,7 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
     async fn instruction_vault_transaction_execute(
         &self,
         sender: Pubkey,
+        mint_info: &MintInfo,
         receiver: Pubkey,
         lamports: u64,
     ) -> Result<Instruction, Self::Error> {
@@ -416,21 +817,7 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
             get_proposal_pda(&self.multisig_pda, transaction_index, Some(&program_id));
         let vault_index = 0;
 
-        let token_program_id = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
-        let token_mint = Pubkey::from_str("Gh9ZwEmdLJ8DscKNTkTqPbNwLNNBjuSzaG9Vp2KGtKJr").unwrap();
-
-        let source_pubkey = get_associated_token_address_with_program_id(&self.vault_pda, &token_mint, &token_program_id);
-        let destination_pubkey = get_associated_token_address_with_program_id(&receiver, &token_mint, &token_program_id);
-
-        println!("{destination_pubkey}");
-
-        let ix = spl_token::instruction::transfer(
-            &token_program_id,
-            &source_pubkey,
-            &destination_pubkey,
-            &self.vault_pda,
-            &[&self.vault_pda],
-            lamports).unwrap();
+        let ix = self.instruction_vault_token_transfer(mint_info, receiver, lamports).await?;
 
         let message = TransactionMessage::try_compile(
             &self.vault_pda,
@@ -459,6 +846,40 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
         }
     }
 
+    fn instruction_vault_transaction_execute_for_index(
+        &self,
+        executer: Pubkey,
+        transaction_index: u64,
+        instructions: &[Instruction],
+        ephemeral_signers: u8,
+    ) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+        let (proposal_pda, _) =
+            get_proposal_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+        let vault_index = 0;
+
+        // Has to be the same message the vault transaction was created with
+        let message = TransactionMessage::try_compile(&self.vault_pda, instructions, &[])
+            .map_err(|_| Self::Error::FailedToCompileVaultTransactionMessage)?;
+
+        vault_transaction_execute(
+            VaultTransactionExecuteAccounts {
+                multisig: self.multisig_pda,
+                transaction: transaction_pda,
+                member: executer,
+                proposal: proposal_pda,
+            },
+            vault_index,
+            ephemeral_signers,
+            &message,
+            &[],
+            Some(program_id),
+        )
+        .map_err(|_| Self::Error::FailedToBuildVaultTransactionExecuteInstruction)
+    }
+
     async fn instruction_change_threshold(
         &self,
         changer: Pubkey,
@@ -478,7 +899,7 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
                 system_program: system_program::ID,
             },
             ConfigTransactionCreateArgs {
-                memo: Some(format!(
+                memo: audit_memo(format!(
                     "Changing threshold to {} on multisig {}",
                     new_threshold, self.multisig_pda
                 )),
@@ -489,6 +910,400 @@ impl BusinessAnalystMultisigTrait<BaseMultisigCreateArgs> for BaseMultisig {
 
         Ok(change_threshold_ix)
     }
+
+    async fn instruction_batch_config(
+        &self,
+        creator: Pubkey,
+        actions: Vec<ConfigAction>,
+    ) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let multisig = self.get_multisig().await?;
+
+        validate_config_actions(&multisig.members, multisig.threshold, &actions)?;
+
+        let transaction_index = multisig.transaction_index + 1;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+
+        let batch_config_ix = config_transaction_create(
+            ConfigTransactionCreateAccounts {
+                multisig: self.multisig_pda,
+                transaction: transaction_pda,
+                creator,
+                rent_payer: creator,
+                system_program: system_program::ID,
+            },
+            ConfigTransactionCreateArgs {
+                memo: audit_memo(format!(
+                    "Applying {} config actions on multisig {}",
+                    actions.len(), self.multisig_pda
+                )),
+                actions,
+            },
+            Some(program_id),
+        );
+
+        Ok(batch_config_ix)
+    }
+
+    async fn instruction_add_spending_limit(
+        &self,
+        creator: Pubkey,
+        args: AddSpendingLimitArgs,
+    ) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let transaction_index = self.get_multisig_transaction_index().await? + 1;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+        let AddSpendingLimitArgs { create_key, mint, amount, period, members, destinations } = args;
+
+        let add_spending_limit_ix = config_transaction_create(
+            ConfigTransactionCreateAccounts {
+                multisig: self.multisig_pda,
+                transaction: transaction_pda,
+                creator,
+                rent_payer: creator,
+                system_program: system_program::ID,
+            },
+            ConfigTransactionCreateArgs {
+                memo: audit_memo(format!(
+                    "Adding spending limit of {amount} {mint} per {:?} on multisig {}",
+                    period, self.multisig_pda
+                )),
+                actions: vec![ConfigAction::AddSpendingLimit {
+                    create_key,
+                    vault_index: 0,
+                    mint,
+                    amount,
+                    period,
+                    members,
+                    destinations,
+                }],
+            },
+            Some(program_id),
+        );
+
+        Ok(add_spending_limit_ix)
+    }
+
+    async fn instruction_remove_spending_limit(
+        &self,
+        remover: Pubkey,
+        spending_limit: Pubkey,
+    ) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let transaction_index = self.get_multisig_transaction_index().await? + 1;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+
+        let remove_spending_limit_ix = config_transaction_create(
+            ConfigTransactionCreateAccounts {
+                multisig: self.multisig_pda,
+                transaction: transaction_pda,
+                creator: remover,
+                rent_payer: remover,
+                system_program: system_program::ID,
+            },
+            ConfigTransactionCreateArgs {
+                memo: audit_memo(format!(
+                    "Removing spending limit {} from multisig {}",
+                    spending_limit, self.multisig_pda
+                )),
+                actions: vec![ConfigAction::RemoveSpendingLimit { spending_limit }],
+            },
+            Some(program_id),
+        );
+
+        Ok(remove_spending_limit_ix)
+    }
+
+    async fn instruction_spending_limit_use(
+        &self,
+        member: Pubkey,
+        spending_limit: Pubkey,
+        receiver: Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
+
+        let spending_limit_account =
+        match self.rpc_client.get_account(&spending_limit).await {
+            Ok(account) => account,
+            Err(_) => return Err(Self::Error::FailedToFetchSpendingLimitAccount)
+        };
+
+        let mut spending_limit_data = spending_limit_account.data.as_slice();
+        let spending_limit_state =
+        match SpendingLimit::try_deserialize(&mut spending_limit_data) {
+            Ok(a) => a,
+            Err(_) => return Err(Self::Error::FailedToDeserializeSpendingLimitData)
+        };
+
+        let mint = spending_limit_state.mint;
+
+        // Squads marks SOL spending limits with the default pubkey as a mint
+        let spending_limit_use_ix = if mint == Pubkey::default() {
+            spending_limit_use(
+                SpendingLimitUseAccounts {
+                    multisig: self.multisig_pda,
+                    member,
+                    spending_limit,
+                    vault: self.vault_pda,
+                    destination: receiver,
+                    system_program: Some(system_program::ID),
+                    mint: None,
+                    vault_token_account: None,
+                    destination_token_account: None,
+                    token_program: None,
+                },
+                SpendingLimitUseArgs {
+                    amount,
+                    decimals: NATIVE_SOL_DECIMALS,
+                    memo: audit_memo(format!("Sending {} SOL ({amount} lamports) to {receiver} with spending limit {spending_limit}", format_ui_amount(amount, NATIVE_SOL_DECIMALS))),
+                },
+                Some(program_id),
+            )
+        } else {
+            let mint_info =
+            match MintInfo::fetch(&self.rpc_client, &mint).await {
+                Ok(mint_info) => mint_info,
+                Err(TokenError::FailedToFetchMint(_)) => return Err(Self::Error::FailedToFetchMintAccount),
+                Err(_) => return Err(Self::Error::FailedToDeserializeMintData)
+            };
+            let token_program_id = mint_info.token_program;
+            let decimals = mint_info.decimals;
+
+            let source_pubkey = mint_info.get_token_account(&self.vault_pda);
+            let destination_pubkey = mint_info.get_token_account(&receiver);
+
+            spending_limit_use(
+                SpendingLimitUseAccounts {
+                    multisig: self.multisig_pda,
+                    member,
+                    spending_limit,
+                    vault: self.vault_pda,
+                    destination: receiver,
+                    system_program: Some(system_program::ID),
+                    mint: Some(mint),
+                    vault_token_account: Some(source_pubkey),
+                    destination_token_account: Some(destination_pubkey),
+                    token_program: Some(token_program_id),
+                },
+                SpendingLimitUseArgs {
+                    amount,
+                    decimals,
+                    memo: audit_memo(format!("Sending {} of {mint} ({amount} base units) to {receiver} with spending limit {spending_limit}", format_ui_amount(amount, decimals))),
+                },
+                Some(program_id),
+            )
+        };
+
+        Ok(spending_limit_use_ix)
+    }
+    fn instruction_transaction_buffer_create(
+        &self,
+        creator: Pubkey,
+        buffer_index: u8,
+        final_buffer_hash: [u8; 32],
+        final_buffer_size: u16,
+        buffer: Vec<u8>,
+    ) -> Instruction {
+        // TransactionBufferCreateArgs
+        let mut args = vec![buffer_index, 0];
+        args.extend_from_slice(&final_buffer_hash);
+        args.extend_from_slice(&final_buffer_size.to_le_bytes());
+        args.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+        args.extend_from_slice(&buffer);
+
+        Instruction {
+            program_id: squads_multisig_program::ID,
+            accounts: vec![
+                AccountMeta::new_readonly(self.multisig_pda, false),
+                AccountMeta::new(get_transaction_buffer_pda(&self.multisig_pda, &creator, buffer_index), false),
+                AccountMeta::new_readonly(creator, true),
+                // rent_payer
+                AccountMeta::new(creator, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: squads_instruction_data("transaction_buffer_create", &args),
+        }
+    }
+
+    fn instruction_transaction_buffer_extend(
+        &self,
+        creator: Pubkey,
+        buffer_index: u8,
+        buffer: Vec<u8>,
+    ) -> Instruction {
+        // TransactionBufferExtendArgs
+        let mut args = (buffer.len() as u32).to_le_bytes().to_vec();
+        args.extend_from_slice(&buffer);
+
+        Instruction {
+            program_id: squads_multisig_program::ID,
+            accounts: vec![
+                AccountMeta::new_readonly(self.multisig_pda, false),
+                AccountMeta::new(get_transaction_buffer_pda(&self.multisig_pda, &creator, buffer_index), false),
+                AccountMeta::new_readonly(creator, true),
+            ],
+            data: squads_instruction_data("transaction_buffer_extend", &args),
+        }
+    }
+
+    fn instruction_transaction_buffer_close(
+        &self,
+        creator: Pubkey,
+        buffer_index: u8,
+    ) -> Instruction {
+        Instruction {
+            program_id: squads_multisig_program::ID,
+            accounts: vec![
+                AccountMeta::new_readonly(self.multisig_pda, false),
+                AccountMeta::new(get_transaction_buffer_pda(&self.multisig_pda, &creator, buffer_index), false),
+                // Receives the rent of the buffer
+                AccountMeta::new(creator, true),
+            ],
+            data: squads_instruction_data("transaction_buffer_close", &[]),
+        }
+    }
+
+    fn instruction_vault_transaction_create_from_buffer(
+        &self,
+        creator: Pubkey,
+        buffer_index: u8,
+        transaction_index: u64,
+        ephemeral_signers: u8,
+        memo: Option<String>,
+    ) -> Result<Instruction, Self::Error> {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+
+        let mut accounts = squads_multisig_program::accounts::VaultTransactionCreate {
+            multisig: self.multisig_pda,
+            transaction: transaction_pda,
+            creator,
+            rent_payer: creator,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(Some(false));
+        accounts.push(AccountMeta::new(get_transaction_buffer_pda(&self.multisig_pda, &creator, buffer_index), false));
+        // Receives the rent of the buffer, which is closed once the transaction is created
+        accounts.push(AccountMeta::new(creator, true));
+
+        let args = VaultTransactionCreateArgs {
+            vault_index: 0,
+            ephemeral_signers,
+            // Squads reads the message from the buffer and expects an empty one here
+            transaction_message: vec![0, 0, 0, 0, 0, 0],
+            memo,
+        }
+        .try_to_vec()
+        .map_err(|_| Self::Error::FailedToBuildVaultTransactionCreateInstruction)?;
+
+        Ok(Instruction {
+            program_id,
+            accounts,
+            data: squads_instruction_data("vault_transaction_create_from_buffer", &args),
+        })
+    }
+
+    fn instruction_config_transaction_accounts_close(
+        &self,
+        rent_collector: Pubkey,
+        transaction_index: u64,
+    ) -> Instruction {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+        let (proposal_pda, _) =
+            get_proposal_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+
+        Instruction {
+            program_id,
+            accounts: squads_multisig_program::accounts::ConfigTransactionAccountsClose {
+                multisig: self.multisig_pda,
+                proposal: proposal_pda,
+                transaction: transaction_pda,
+                rent_collector,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(Some(false)),
+            data: squads_multisig_program::instruction::ConfigTransactionAccountsClose {}.data(),
+        }
+    }
+
+    fn instruction_vault_transaction_accounts_close(
+        &self,
+        rent_collector: Pubkey,
+        transaction_index: u64,
+    ) -> Instruction {
+        let program_id: Pubkey = squads_multisig_program::ID;
+        let (transaction_pda, _) =
+            get_transaction_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+        let (proposal_pda, _) =
+            get_proposal_pda(&self.multisig_pda, transaction_index, Some(&program_id));
+
+        Instruction {
+            program_id,
+            accounts: squads_multisig_program::accounts::VaultTransactionAccountsClose {
+                multisig: self.multisig_pda,
+                proposal: proposal_pda,
+                transaction: transaction_pda,
+                rent_collector,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(Some(false)),
+            data: squads_multisig_program::instruction::VaultTransactionAccountsClose {}.data(),
+        }
+    }
+}
+
+/// Pda of the transaction buffer `creator` uploads a vault transaction message to
+pub fn get_transaction_buffer_pda(multisig_pda: &Pubkey, creator: &Pubkey, buffer_index: u8) -> Pubkey {
+    let program_id: Pubkey = squads_multisig_program::ID;
+    let (transaction_buffer_pda, _) = Pubkey::find_program_address(
+        &[b"multisig", multisig_pda.as_ref(), b"transaction_buffer", creator.as_ref(), &[buffer_index]],
+        &program_id,
+    );
+
+    transaction_buffer_pda
+}
+
+/// Anchor discriminator of the squads instruction `name`, for instructions the program crate this is built
+/// against does not define yet
+pub fn squads_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{name}").as_bytes()).to_bytes()[..8]);
+
+    discriminator
+}
+
+/// Data of a squads instruction the program crate this is built against does not define yet: the anchor
+/// discriminator of `name` followed by the borsh encoded arguments
+fn squads_instruction_data(name: &str, args: &[u8]) -> Vec<u8> {
+    let mut data = squads_discriminator(name).to_vec();
+    data.extend_from_slice(args);
+
+    data
+}
+
+/// Whether `instructions` paid by `payer` fit in one packet, leaving room for the compute budget instructions
+pub fn fits_in_transaction(payer: &Pubkey, instructions: &[Instruction]) -> bool {
+    let tx = Transaction::new_unsigned(Message::new(instructions, Some(payer)));
+
+    match bincode::serialized_size(&tx) {
+        Ok(size) => size as usize + COMPUTE_BUDGET_RESERVED_SIZE <= PACKET_DATA_SIZE,
+        Err(_) => false,
+    }
+}
+
+/// Pda of the spending limit created by `instruction_add_spending_limit` with the same create key
+pub fn get_multisig_spending_limit_pda(multisig_pda: &Pubkey, spending_limit_create_key: &Pubkey) -> Pubkey {
+    let program_id: Pubkey = squads_multisig_program::ID;
+    let (spending_limit_pda, _) = get_spending_limit_pda(multisig_pda, spending_limit_create_key, Some(&program_id));
+
+    spending_limit_pda
 }
 
 #[cfg(test)]
@@ -498,11 +1313,13 @@ mod tests {
     use crate::domain::entities::multisig::error::BaseMultisigError;
 
     use super::*;
+    use crate::contract_module::associated_token::native::{get_wrapped_sol_balance, instructions_wrap_sol};
     use solana_client::nonblocking::rpc_client::RpcClient;
     use solana_sdk::{
         native_token::LAMPORTS_PER_SOL,
         signature::{Keypair, Signature},
     };
+    use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
     use tokio;
 
     async fn transaction_sign_and_send(
@@ -568,6 +1385,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
     async fn create_multisig_no_members() -> Result<(), Box<dyn Error>> {
         let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
         let creator: Keypair = Keypair::new();
@@ -583,6 +1401,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
     async fn add_member() -> Result<(), Box<dyn Error>> {
         let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
         let creator: Keypair = Keypair::new();
@@ -636,6 +1455,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
     async fn change_threshold() -> Result<(), Box<dyn Error>> {
         let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
         let creator: Keypair = Keypair::new();
@@ -690,6 +1510,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
     async fn vault_transaction_member_approve() -> Result<(), Box<dyn Error>> {
         let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
         let creator: Keypair = Keypair::new();
@@ -701,19 +1522,37 @@ mod tests {
             permissions: Permissions::from_vec(&[Permission::Vote]),
         };
 
-        let _ = airdrop(&rpc_client, &creator.pubkey(), 1).await?;
+        let _ = airdrop(&rpc_client, &creator.pubkey(), 4).await?;
         let _ = airdrop(&rpc_client, &member.pubkey(), 1).await?;
 
         let base_multisig = get_base_multisig(&rpc_client, &create_key, &creator, &[new_member])
             .await
             .unwrap();
         let multisig = get_ba_multisig(&base_multisig).await.unwrap();
+        let mint_info = MintInfo::fetch(&rpc_client, &spl_token::native_mint::id()).await.unwrap();
 
-        let _ = airdrop(&rpc_client, &multisig.get_vault_pda(), 3).await?;
+        // The vault transfers wSOL, wrap SOL of the creator into the vault and give the member a wSOL account
+        let mut instructions = instructions_wrap_sol(&rpc_client, &creator.pubkey(), &multisig.get_vault_pda(), 3 * LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+        instructions.push(create_associated_token_account_idempotent(
+            &creator.pubkey(),
+            &member.pubkey(),
+            &mint_info.mint,
+            &mint_info.token_program,
+        ));
+        let mut tx = multisig
+            .get_transaction_from_instructions(creator.pubkey(), &instructions)
+            .await
+            .unwrap();
+        transaction_sign_and_send(&mut tx, &[&creator], &rpc_client)
+            .await
+            .unwrap();
 
         let mut tx = multisig
             .transaction_transfer_from_vault(
                 creator.pubkey(),
+                &mint_info,
                 member.pubkey(),
                 2 * LAMPORTS_PER_SOL,
             )
@@ -742,6 +1581,7 @@ mod tests {
         let mut tx = multisig
             .transaction_vault_transaction_execute(
                 creator.pubkey(),
+                &mint_info,
                 member.pubkey(),
                 2 * LAMPORTS_PER_SOL,
             )
@@ -751,14 +1591,188 @@ mod tests {
             .await
             .unwrap();
 
-        assert!(rpc_client.get_balance(&member.pubkey()).await.unwrap() > 2 * LAMPORTS_PER_SOL);
         assert_eq!(
-            rpc_client
-                .get_balance(&multisig.get_vault_pda())
+            2 * LAMPORTS_PER_SOL,
+            get_wrapped_sol_balance(&rpc_client, &member.pubkey()).await.unwrap()
+        );
+        assert_eq!(
+            LAMPORTS_PER_SOL,
+            get_wrapped_sol_balance(&rpc_client, &multisig.get_vault_pda()).await.unwrap()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
+    async fn vault_transaction_create_from_buffer() -> Result<(), Box<dyn Error>> {
+        let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
+        let creator: Keypair = Keypair::new();
+        let create_key = Keypair::new();
+
+        let _ = airdrop(&rpc_client, &creator.pubkey(), 1).await?;
+
+        let base_multisig = get_base_multisig(&rpc_client, &create_key, &creator, &[])
+            .await
+            .unwrap();
+        let multisig = get_ba_multisig(&base_multisig).await.unwrap();
+
+        // Too many transfers for one vault_transaction_create
+        let transfers: Vec<Instruction> = (0..30)
+            .map(|_| solana_sdk::system_instruction::transfer(&multisig.get_vault_pda(), &Pubkey::new_unique(), 1))
+            .collect();
+
+        let transaction_index = multisig.get_multisig_transaction_index().await.unwrap() + 1;
+        let groups = multisig
+            .instructions_vault_transaction_create(creator.pubkey(), transaction_index, &transfers, 0, None)
+            .await
+            .unwrap();
+        assert!(groups.len() > 2);
+
+        for group in groups {
+            let mut tx = multisig
+                .get_transaction_from_instructions(creator.pubkey(), &group)
                 .await
+                .unwrap();
+            transaction_sign_and_send(&mut tx, &[&creator], &rpc_client)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(transaction_index, multisig.get_multisig_transaction_index().await.unwrap());
+        assert!(rpc_client
+            .get_account(&get_transaction_buffer_pda(&multisig.get_multisig_pda(), &creator.pubkey(), 0))
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    /// Arguments of the squads transaction buffer instructions, fields in the order of the program
+    #[derive(borsh::BorshSerialize)]
+    struct TransactionBufferCreateArgs {
+        buffer_index: u8,
+        vault_index: u8,
+        final_buffer_hash: [u8; 32],
+        final_buffer_size: u16,
+        buffer: Vec<u8>,
+    }
+
+    #[derive(borsh::BorshSerialize)]
+    struct TransactionBufferExtendArgs {
+        buffer: Vec<u8>,
+    }
+
+    /// Multisig for building instructions, nothing is fetched so no validator is needed
+    fn offline_multisig() -> BaseMultisig {
+        BaseMultisig {
+            rpc_client: RpcClient::new("http://127.0.0.1:8899".to_string()),
+            multisig_create_keypair: None,
+            creator: Pubkey::new_unique(),
+            multisig_pda: Pubkey::new_unique(),
+            vault_pda: Pubkey::new_unique(),
+            program_config_pda: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+        }
+    }
+
+    fn instruction_data(name: &str, args: impl borsh::BorshSerialize) -> Vec<u8> {
+        let mut data = squads_discriminator(name).to_vec();
+        data.extend(borsh::to_vec(&args).unwrap());
+
+        data
+    }
+
+    #[test]
+    fn squads_discriminators_are_anchor_discriminators() {
+        use squads_multisig::anchor_lang::Discriminator;
+
+        assert_eq!(squads_multisig_program::instruction::VaultTransactionCreate::DISCRIMINATOR, squads_discriminator("vault_transaction_create"));
+        assert_eq!(squads_multisig_program::instruction::ConfigTransactionAccountsClose::DISCRIMINATOR, squads_discriminator("config_transaction_accounts_close"));
+    }
+
+    #[test]
+    fn transaction_buffer_instructions_follow_program_layout() {
+        let multisig = offline_multisig();
+        let creator = Pubkey::new_unique();
+        let buffer = vec![7; 40];
+        let final_buffer_hash = hash(&buffer).to_bytes();
+        let transaction_buffer = get_transaction_buffer_pda(&multisig.multisig_pda, &creator, 3);
+
+        let create = multisig.instruction_transaction_buffer_create(creator, 3, final_buffer_hash, 80, buffer.clone());
+        assert_eq!(squads_multisig_program::ID, create.program_id);
+        assert_eq!(
+            instruction_data(
+                "transaction_buffer_create",
+                TransactionBufferCreateArgs { buffer_index: 3, vault_index: 0, final_buffer_hash, final_buffer_size: 80, buffer: buffer.clone() },
+            ),
+            create.data
+        );
+        // multisig, transaction_buffer, creator, rent_payer, system_program
+        assert_eq!(
+            vec![
+                AccountMeta::new_readonly(multisig.multisig_pda, false),
+                AccountMeta::new(transaction_buffer, false),
+                AccountMeta::new_readonly(creator, true),
+                AccountMeta::new(creator, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            create.accounts
+        );
+
+        let extend = multisig.instruction_transaction_buffer_extend(creator, 3, buffer.clone());
+        assert_eq!(instruction_data("transaction_buffer_extend", TransactionBufferExtendArgs { buffer }), extend.data);
+        // multisig, transaction_buffer, creator
+        assert_eq!(
+            vec![
+                AccountMeta::new_readonly(multisig.multisig_pda, false),
+                AccountMeta::new(transaction_buffer, false),
+                AccountMeta::new_readonly(creator, true),
+            ],
+            extend.accounts
+        );
+
+        let close = multisig.instruction_transaction_buffer_close(creator, 3);
+        assert_eq!(squads_discriminator("transaction_buffer_close").to_vec(), close.data);
+        // multisig, transaction_buffer, creator receiving the rent
+        assert_eq!(
+            vec![
+                AccountMeta::new_readonly(multisig.multisig_pda, false),
+                AccountMeta::new(transaction_buffer, false),
+                AccountMeta::new(creator, true),
+            ],
+            close.accounts
+        );
+    }
+
+    #[test]
+    fn vault_transaction_create_from_buffer_follows_program_layout() {
+        let multisig = offline_multisig();
+        let creator = Pubkey::new_unique();
+        let memo = Some("Vault transaction 4".to_string());
+        let (transaction_pda, _) = get_transaction_pda(&multisig.multisig_pda, 4, Some(&squads_multisig_program::ID));
+
+        let create = multisig
+            .instruction_vault_transaction_create_from_buffer(creator, 3, 4, 2, memo.clone())
+            .unwrap();
+
+        let mut data = squads_discriminator("vault_transaction_create_from_buffer").to_vec();
+        data.extend(
+            VaultTransactionCreateArgs { vault_index: 0, ephemeral_signers: 2, transaction_message: vec![0, 0, 0, 0, 0, 0], memo }
+                .try_to_vec()
                 .unwrap(),
-            1 * LAMPORTS_PER_SOL
         );
-        Ok(())
+        assert_eq!(data, create.data);
+        // vault_transaction_create accounts, then transaction_buffer and creator
+        assert_eq!(
+            vec![
+                AccountMeta::new(multisig.multisig_pda, false),
+                AccountMeta::new(transaction_pda, false),
+                AccountMeta::new_readonly(creator, true),
+                AccountMeta::new(creator, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(get_transaction_buffer_pda(&multisig.multisig_pda, &creator, 3), false),
+                AccountMeta::new(creator, true),
+            ],
+            create.accounts
+        );
     }
 }
@@ -0,0 +1,170 @@
+use solana_sdk::pubkey::Pubkey;
+use squads_multisig::state::{ConfigAction, Member, Permission};
+
+use super::error::BaseMultisigError;
+
+/// Same limit the squads program enforces on `SetTimeLock`, 3 months in seconds
+pub const MAX_TIME_LOCK: u32 = 3 * 30 * 24 * 60 * 60;
+
+/// Applies `actions` to a copy of the current config and checks the result against the squads invariants,
+/// so a batch that would fail on `config_transaction_execute` is rejected before anything is sent
+pub fn validate_config_actions(
+    members: &[Member],
+    threshold: u16,
+    actions: &[ConfigAction],
+) -> Result<(), BaseMultisigError> {
+    if actions.is_empty() {
+        return Err(BaseMultisigError::EmptyConfigActions);
+    }
+
+    let mut members: Vec<Member> = members.to_vec();
+    let mut threshold = threshold;
+
+    for action in actions {
+        match action {
+            ConfigAction::AddMember { new_member } => {
+                if find_member(&members, &new_member.key).is_some() {
+                    return Err(BaseMultisigError::DuplicateMember);
+                }
+                members.push(new_member.clone());
+            },
+            ConfigAction::RemoveMember { old_member } => {
+                if members.len() == 1 {
+                    return Err(BaseMultisigError::RemoveLastMember);
+                }
+                match find_member(&members, old_member) {
+                    Some(index) => { members.remove(index); },
+                    None => return Err(BaseMultisigError::MemberNotFound)
+                }
+            },
+            ConfigAction::ChangeThreshold { new_threshold } => {
+                threshold = *new_threshold;
+            },
+            ConfigAction::SetTimeLock { new_time_lock } if *new_time_lock > MAX_TIME_LOCK => {
+                return Err(BaseMultisigError::TimeLockExceedsMax);
+            },
+            _ => {}
+        }
+    }
+
+    let count = |permission: Permission| {
+        members.iter().filter(|member| member.permissions.has(permission)).count()
+    };
+
+    if count(Permission::Initiate) == 0 {
+        return Err(BaseMultisigError::NoProposers);
+    }
+    if count(Permission::Execute) == 0 {
+        return Err(BaseMultisigError::NoExecutors);
+    }
+
+    let voters = count(Permission::Vote);
+    if voters == 0 {
+        return Err(BaseMultisigError::NoVoters);
+    }
+    if threshold == 0 || threshold as usize > voters {
+        return Err(BaseMultisigError::InvalidThreshold);
+    }
+
+    Ok(())
+}
+
+fn find_member(members: &[Member], key: &Pubkey) -> Option<usize> {
+    members.iter().position(|member| member.key == *key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use squads_multisig::state::Permissions;
+
+    fn ba() -> Member {
+        Member {
+            key: Pubkey::new_unique(),
+            permissions: Permissions::from_vec(&[Permission::Initiate, Permission::Vote, Permission::Execute]),
+        }
+    }
+
+    fn investor() -> Member {
+        Member {
+            key: Pubkey::new_unique(),
+            permissions: Permissions::from_vec(&[Permission::Vote]),
+        }
+    }
+
+    #[test]
+    fn add_members_and_raise_threshold() {
+        let members = [ba()];
+        let actions = [
+            ConfigAction::AddMember { new_member: investor() },
+            ConfigAction::AddMember { new_member: investor() },
+            ConfigAction::ChangeThreshold { new_threshold: 2 },
+        ];
+
+        assert!(validate_config_actions(&members, 1, &actions).is_ok());
+    }
+
+    #[test]
+    fn threshold_above_voters_is_rejected() {
+        let members = [ba(), investor()];
+        let actions = [ConfigAction::ChangeThreshold { new_threshold: 3 }];
+
+        assert!(matches!(
+            validate_config_actions(&members, 1, &actions),
+            Err(BaseMultisigError::InvalidThreshold)
+        ));
+    }
+
+    #[test]
+    fn removing_voter_below_threshold_is_rejected() {
+        let investor = investor();
+        let members = [ba(), investor.clone()];
+        let actions = [ConfigAction::RemoveMember { old_member: investor.key }];
+
+        assert!(matches!(
+            validate_config_actions(&members, 2, &actions),
+            Err(BaseMultisigError::InvalidThreshold)
+        ));
+    }
+
+    #[test]
+    fn removing_last_executor_is_rejected() {
+        let ba = ba();
+        let members = [ba.clone(), investor()];
+        let actions = [ConfigAction::RemoveMember { old_member: ba.key }];
+
+        assert!(matches!(
+            validate_config_actions(&members, 1, &actions),
+            Err(BaseMultisigError::NoProposers)
+        ));
+    }
+
+    #[test]
+    fn duplicate_and_unknown_members_are_rejected() {
+        let ba = ba();
+        let members = [ba.clone()];
+
+        assert!(matches!(
+            validate_config_actions(&members, 1, &[ConfigAction::AddMember { new_member: ba.clone() }]),
+            Err(BaseMultisigError::DuplicateMember)
+        ));
+        assert!(matches!(
+            validate_config_actions(&[ba.clone(), investor()], 1, &[ConfigAction::RemoveMember { old_member: Pubkey::new_unique() }]),
+            Err(BaseMultisigError::MemberNotFound)
+        ));
+    }
+
+    #[test]
+    fn time_lock_and_empty_batch_are_checked() {
+        let members = [ba()];
+
+        assert!(matches!(
+            validate_config_actions(&members, 1, &[ConfigAction::SetTimeLock { new_time_lock: MAX_TIME_LOCK + 1 }]),
+            Err(BaseMultisigError::TimeLockExceedsMax)
+        ));
+        assert!(matches!(
+            validate_config_actions(&members, 1, &[]),
+            Err(BaseMultisigError::EmptyConfigActions)
+        ));
+    }
+}
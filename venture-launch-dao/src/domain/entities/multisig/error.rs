@@ -20,7 +20,53 @@ pub enum BaseMultisigError {
     #[error("Error on getting latest block hash")]
     ErrorOnGettingLatestBlockHash,
     #[error("Proposal status is not Approved")]
-    ProposalStatusIsNotApproved
+    ProposalStatusIsNotApproved,
+    #[error("Failed to fetch spending limit accounts")]
+    FailedToFetchSpendingLimitAccounts,
+    #[error("Failed to fetch spending limit account")]
+    FailedToFetchSpendingLimitAccount,
+    #[error("Failed to deserialize spending limit account")]
+    FailedToDeserializeSpendingLimitData,
+    #[error("Failed to fetch mint account")]
+    FailedToFetchMintAccount,
+    #[error("Failed to deserialize mint account")]
+    FailedToDeserializeMintData,
+    #[error("Failed to build transfer instruction")]
+    FailedToBuildTransferInstruction,
+    #[error("No config actions were provided")]
+    EmptyConfigActions,
+    #[error("Member is already in the multisig")]
+    DuplicateMember,
+    #[error("Member is not in the multisig")]
+    MemberNotFound,
+    #[error("Multisig must keep at least one member")]
+    RemoveLastMember,
+    #[error("Threshold must be between 1 and the number of voters")]
+    InvalidThreshold,
+    #[error("Multisig must keep at least one member with Initiate permission")]
+    NoProposers,
+    #[error("Multisig must keep at least one member with Vote permission")]
+    NoVoters,
+    #[error("Multisig must keep at least one member with Execute permission")]
+    NoExecutors,
+    #[error("Time lock exceeds the maximum allowed by squads")]
+    TimeLockExceedsMax,
+    #[error("Failed to compile vault transaction message")]
+    FailedToCompileVaultTransactionMessage,
+    #[error("Vault transaction message exceeds the transaction buffer size")]
+    TransactionBufferTooLarge,
+    #[error("Failed to fetch rent exemption")]
+    FailedToFetchRent,
+    #[error("Failed to build VentureLaunch instruction")]
+    FailedToBuildVentureLaunchInstruction,
+    #[error("Failed to fetch vault balances")]
+    FailedToFetchVaultBalances,
+    #[error("Failed to build vault_transaction_create_from_buffer instruction")]
+    FailedToBuildVaultTransactionCreateInstruction,
+    #[error("Failed to fetch transaction buffer accounts")]
+    FailedToFetchTransactionBuffers,
+    #[error("Every transaction buffer index of the creator is taken, close a transaction buffer first")]
+    NoFreeTransactionBufferIndex
 }
 
 impl From<BaseMultisigError> for ProgramError {
@@ -70,6 +70,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
     async fn create_multisig_with_investor() -> Result<(), Box<dyn Error>> {
         let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
         let ba: Keypair = Keypair::new();
@@ -91,6 +92,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
     async fn approve_proposal() -> Result<(), Box<dyn Error>> {
         let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
         let ba: Keypair = Keypair::new();
@@ -127,6 +129,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs a local validator at localhost:8899"]
     async fn proposal_cancel() -> Result<(), Box<dyn Error>> {
         let rpc_client = RpcClient::new("http://127.0.0.1:8899".to_string());
         let ba: Keypair = Keypair::new();
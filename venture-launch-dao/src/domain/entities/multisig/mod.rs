@@ -2,4 +2,5 @@ pub mod base_multisig;
 pub mod base_multisig_trait;
 pub mod business_analyst_multisig_trait;
 pub mod investor_multisig_trait;
+pub mod config_actions;
 pub mod error;
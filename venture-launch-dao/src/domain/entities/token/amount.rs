@@ -0,0 +1,115 @@
+use serde::Deserialize;
+
+use super::error::TokenError;
+
+/// Amount of a request, a JSON number is in base units and a JSON string like `"125.5"` is in whole tokens
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum TokenAmount {
+    Raw(u64),
+    Ui(String),
+}
+
+impl TokenAmount {
+    /// Amount in base units of a mint with `decimals`
+    pub fn to_raw(&self, decimals: u8) -> Result<u64, TokenError> {
+        match self {
+            Self::Raw(amount) => Ok(*amount),
+            Self::Ui(amount) => parse_ui_amount(amount, decimals),
+        }
+    }
+}
+
+/// Base units of a decimal string amount, computed on integers so nothing gets rounded.
+/// Fails when the amount has more fraction digits than the mint has decimals
+pub fn parse_ui_amount(amount: &str, decimals: u8) -> Result<u64, TokenError> {
+    let invalid = || TokenError::InvalidAmount(amount.to_string());
+
+    let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    // Trailing zeros add no precision, "1.50" is fine for a mint with 1 decimal
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        return Err(TokenError::TooManyDecimals { amount: amount.to_string(), decimals });
+    }
+
+    let scale = 10u64.checked_pow(decimals as u32).ok_or_else(invalid)?;
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+    let fraction: u64 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<width$}", width = decimals as usize).parse().map_err(|_| invalid())?
+    };
+
+    whole
+        .checked_mul(scale)
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+/// Decimal string of `amount` base units of a mint with `decimals`, without trailing zeros
+pub fn format_ui_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let digits = format!("{amount:0>width$}", width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_strings_without_rounding() {
+        assert_eq!(125_500_000, parse_ui_amount("125.5", 6).unwrap());
+        assert_eq!(1, parse_ui_amount("0.000001", 6).unwrap());
+        assert_eq!(500_000, parse_ui_amount(".5", 6).unwrap());
+        assert_eq!(7_000_000, parse_ui_amount("7", 6).unwrap());
+        assert_eq!(15, parse_ui_amount("1.50", 1).unwrap());
+        // 0.1 + 0.2 style float errors would show here
+        assert_eq!(300_000_000, parse_ui_amount("0.3", 9).unwrap());
+        assert_eq!(u64::MAX, parse_ui_amount("18446744073709.551615", 6).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_amounts() {
+        assert!(matches!(parse_ui_amount("0.0000001", 6), Err(TokenError::TooManyDecimals { decimals: 6, .. })));
+        assert!(matches!(parse_ui_amount("1.5", 0), Err(TokenError::TooManyDecimals { decimals: 0, .. })));
+        for amount in ["", ".", "-1", "1e6", "1.2.3", "1,5", "18446744073709.551616"] {
+            assert!(matches!(parse_ui_amount(amount, 6), Err(TokenError::InvalidAmount(_))), "{amount}");
+        }
+    }
+
+    #[test]
+    fn formats_base_units() {
+        assert_eq!("125.5", format_ui_amount(125_500_000, 6));
+        assert_eq!("0.000001", format_ui_amount(1, 6));
+        assert_eq!("0", format_ui_amount(0, 6));
+        assert_eq!("42", format_ui_amount(42, 0));
+        assert_eq!("18446744073709.551615", format_ui_amount(u64::MAX, 6));
+    }
+
+    #[test]
+    fn numbers_are_base_units_and_strings_whole_tokens() {
+        let raw: TokenAmount = serde_json::from_str("1000").unwrap();
+        let ui: TokenAmount = serde_json::from_str("\"1000\"").unwrap();
+
+        assert_eq!(1000, raw.to_raw(6).unwrap());
+        assert_eq!(1_000_000_000, ui.to_raw(6).unwrap());
+    }
+}
@@ -0,0 +1,26 @@
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone)]
+pub enum TokenError {
+    #[error("Failed to fetch mint {0}")]
+    FailedToFetchMint(Pubkey),
+    #[error("{0} is not a mint")]
+    InvalidMint(Pubkey),
+    #[error("Mint {mint} is owned by {owner}, not by a token program")]
+    UnsupportedTokenProgram { mint: Pubkey, owner: Pubkey },
+    #[error("{0} is not a token account")]
+    InvalidTokenAccount(Pubkey),
+    #[error("Failed to fetch current epoch")]
+    FailedToFetchEpoch,
+    #[error("Transfer fee of {amount} overflows")]
+    InvalidTransferFee { amount: u64 },
+    #[error("{0} is not a decimal amount of base units or whole tokens")]
+    InvalidAmount(String),
+    #[error("{amount} has more than the {decimals} decimals of the mint")]
+    TooManyDecimals { amount: String, decimals: u8 },
+    #[error("Failed to build transfer: {0}")]
+    FailedToBuildTransfer(String),
+    #[error("Failed to resolve transfer hook accounts of {mint}: {err}")]
+    FailedToResolveTransferHook { mint: Pubkey, err: String },
+}
@@ -0,0 +1,232 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    account::Account, clock::Epoch, instruction::Instruction, program_pack::Pack, pubkey::Pubkey
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::extension::{
+    transfer_fee::TransferFeeConfig, transfer_hook, BaseStateWithExtensions, ExtensionType, StateWithExtensions
+};
+use spl_token_2022::offchain::{create_transfer_checked_instruction_with_extra_metas, AccountFetchError};
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+
+use super::error::TokenError;
+
+/// Whether `program_id` is the token program or the Token-2022 program
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::id() || *program_id == spl_token_2022::id()
+}
+
+/// What a transfer of a mint needs to know, read from the mint account of either token program
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MintInfo {
+    pub mint: Pubkey,
+    /// Program owning the mint, every token account of the mint belongs to the same program
+    pub token_program: Pubkey,
+    pub decimals: u8,
+    /// Token-2022 transfer fee withheld from every transfer
+    pub transfer_fee: Option<TransferFeeConfig>,
+    /// Token-2022 program invoked on every transfer, its extra accounts have to be passed along
+    pub transfer_hook_program: Option<Pubkey>,
+    /// Size of an associated token account of the mint, Token-2022 mints add extensions to their accounts
+    pub token_account_len: usize,
+}
+
+impl MintInfo {
+    pub fn from_account(mint: &Pubkey, account: &Account) -> Result<Self, TokenError> {
+        if !is_token_program(&account.owner) {
+            return Err(TokenError::UnsupportedTokenProgram { mint: *mint, owner: account.owner });
+        }
+
+        // Reads token program mints as well, they are Token-2022 mints without extensions
+        let state = StateWithExtensions::<Mint>::unpack(&account.data).map_err(|_| TokenError::InvalidMint(*mint))?;
+        let token_account_len = if account.owner == spl_token_2022::id() {
+            // The associated token account program always adds the immutable owner extension
+            let mut extensions = ExtensionType::get_required_init_account_extensions(
+                &state.get_extension_types().map_err(|_| TokenError::InvalidMint(*mint))?,
+            );
+            extensions.push(ExtensionType::ImmutableOwner);
+            ExtensionType::try_calculate_account_len::<TokenAccount>(&extensions).map_err(|_| TokenError::InvalidMint(*mint))?
+        } else {
+            TokenAccount::LEN
+        };
+
+        Ok(Self {
+            mint: *mint,
+            token_program: account.owner,
+            decimals: state.base.decimals,
+            transfer_fee: state.get_extension::<TransferFeeConfig>().ok().copied(),
+            transfer_hook_program: transfer_hook::get_program_id(&state),
+            token_account_len,
+        })
+    }
+
+    pub async fn fetch(rpc_client: &RpcClient, mint: &Pubkey) -> Result<Self, TokenError> {
+        let account = rpc_client
+            .get_account(mint)
+            .await
+            .map_err(|_| TokenError::FailedToFetchMint(*mint))?;
+
+        Self::from_account(mint, &account)
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.token_program == spl_token_2022::id()
+    }
+
+    /// Associated token account of `owner` for the mint, under the program owning the mint
+    pub fn get_token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+
+    /// Fee withheld when `amount` is transferred during `epoch`
+    pub fn get_transfer_fee(&self, epoch: Epoch, amount: u64) -> Result<u64, TokenError> {
+        match &self.transfer_fee {
+            Some(transfer_fee) => transfer_fee
+                .calculate_epoch_fee(epoch, amount)
+                .ok_or(TokenError::InvalidTransferFee { amount }),
+            None => Ok(0)
+        }
+    }
+
+    /// Amount the receiver gets when `amount` is transferred during `epoch`
+    pub fn get_net_amount(&self, epoch: Epoch, amount: u64) -> Result<u64, TokenError> {
+        Ok(amount - self.get_transfer_fee(epoch, amount)?)
+    }
+
+    /// `transfer_checked` of `amount` from `source` to `destination`, token accounts of the mint.
+    /// Transfer hook mints get the extra accounts of their hook program resolved from chain
+    pub async fn instruction_transfer_checked(
+        &self,
+        rpc_client: &RpcClient,
+        source: &Pubkey,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        amount: u64,
+    ) -> Result<Instruction, TokenError> {
+        if self.transfer_hook_program.is_none() {
+            return spl_token_2022::instruction::transfer_checked(
+                &self.token_program,
+                source,
+                &self.mint,
+                destination,
+                authority,
+                &[],
+                amount,
+                self.decimals,
+            )
+            .map_err(|err| TokenError::FailedToBuildTransfer(err.to_string()));
+        }
+
+        create_transfer_checked_instruction_with_extra_metas(
+            &self.token_program,
+            source,
+            &self.mint,
+            destination,
+            authority,
+            &[],
+            amount,
+            self.decimals,
+            |address| async move {
+                rpc_client
+                    .get_account_with_commitment(&address, rpc_client.commitment())
+                    .await
+                    .map(|response| response.value.map(|account| account.data))
+                    .map_err(|err| AccountFetchError::from(err.to_string()))
+            },
+        )
+        .await
+        .map_err(|err| TokenError::FailedToResolveTransferHook { mint: self.mint, err: err.to_string() })
+    }
+}
+
+/// Current epoch, transfer fees change by epoch
+pub async fn get_epoch(rpc_client: &RpcClient) -> Result<Epoch, TokenError> {
+    Ok(rpc_client.get_epoch_info().await.map_err(|_| TokenError::FailedToFetchEpoch)?.epoch)
+}
+
+/// Token account of either token program, extensions are skipped
+pub fn unpack_token_account(address: &Pubkey, account: &Account) -> Result<TokenAccount, TokenError> {
+    if !is_token_program(&account.owner) {
+        return Err(TokenError::InvalidTokenAccount(*address));
+    }
+
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .map(|state| state.base)
+        .map_err(|_| TokenError::InvalidTokenAccount(*address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token_2022::extension::{transfer_fee::TransferFee, BaseStateWithExtensionsMut, StateWithExtensionsMut};
+
+    fn mint_account(owner: Pubkey, data: Vec<u8>) -> Account {
+        Account { lamports: 1, data, owner, executable: false, rent_epoch: 0 }
+    }
+
+    fn base_mint() -> Mint {
+        Mint { decimals: 6, is_initialized: true, ..Mint::default() }
+    }
+
+    fn transfer_fee(basis_points: u16, maximum_fee: u64) -> TransferFee {
+        TransferFee {
+            epoch: 0.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: basis_points.into(),
+        }
+    }
+
+    #[test]
+    fn reads_token_program_mint() {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(base_mint(), &mut data).unwrap();
+
+        let info = MintInfo::from_account(&mint, &mint_account(spl_token::id(), data)).unwrap();
+
+        assert_eq!(spl_token::id(), info.token_program);
+        assert_eq!(6, info.decimals);
+        assert!(!info.is_token_2022());
+        assert_eq!(None, info.transfer_fee);
+        assert_eq!(TokenAccount::LEN, info.token_account_len);
+        assert_eq!(1_000, info.get_net_amount(0, 1_000).unwrap());
+        let owner = Pubkey::new_unique();
+        assert_eq!(spl_associated_token_account::get_associated_token_address(&owner, &mint), info.get_token_account(&owner));
+    }
+
+    #[test]
+    fn reads_transfer_fee_of_token_2022_mint() {
+        let mint = Pubkey::new_unique();
+        let len = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+        let mut data = vec![0; len];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        let extension = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        extension.older_transfer_fee = transfer_fee(100, 5);
+        extension.newer_transfer_fee = transfer_fee(100, 5);
+        state.base = base_mint();
+        state.pack_base();
+        state.init_account_type().unwrap();
+
+        let info = MintInfo::from_account(&mint, &mint_account(spl_token_2022::id(), data)).unwrap();
+
+        assert!(info.is_token_2022());
+        assert_eq!(None, info.transfer_hook_program);
+        assert_eq!(
+            ExtensionType::try_calculate_account_len::<TokenAccount>(&[ExtensionType::TransferFeeAmount, ExtensionType::ImmutableOwner]).unwrap(),
+            info.token_account_len
+        );
+        // 1% of 200 is 2, of 1_000 it is 10 but capped at 5
+        assert_eq!(198, info.get_net_amount(0, 200).unwrap());
+        assert_eq!(995, info.get_net_amount(0, 1_000).unwrap());
+    }
+
+    #[test]
+    fn rejects_mint_of_other_program() {
+        let mint = Pubkey::new_unique();
+
+        assert!(matches!(
+            MintInfo::from_account(&mint, &mint_account(Pubkey::new_unique(), vec![0; Mint::LEN])),
+            Err(TokenError::UnsupportedTokenProgram { .. })
+        ));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod amount;
+pub mod mint;
+pub mod error;
\ No newline at end of file
@@ -0,0 +1,223 @@
+use std::str::FromStr;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+use squads_multisig::{
+    anchor_lang::{AnchorDeserialize, Discriminator},
+    squads_multisig_program::{self, instruction as squads_instruction, VaultTransactionCreateArgs},
+};
+
+use crate::domain::entities::memo::audit_memo::AuditMemo;
+use crate::domain::entities::multisig::business_analyst_multisig_trait::squads_discriminator;
+
+use super::error::AuditError;
+
+/// Signatures fetched per `getSignaturesForAddress` call, the most the RPC returns
+const SIGNATURES_PAGE_SIZE: usize = 1000;
+
+/// Squads instruction of a DAO carrying a platform memo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub instruction: &'static str,
+    pub fee_payer: Pubkey,
+    pub memo: AuditMemo,
+}
+
+impl AuditEntry {
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut entry = serde_json::json!({
+            "signature": self.signature.to_string(),
+            "slot": self.slot,
+            "block_time": self.block_time,
+            "instruction": self.instruction,
+            "fee_payer": self.fee_payer.to_string(),
+        });
+        if let (Some(entry), Ok(serde_json::Value::Object(memo))) = (entry.as_object_mut(), serde_json::to_value(&self.memo)) {
+            entry.extend(memo);
+        }
+
+        entry
+    }
+}
+
+/// Platform history of the DAO at `multisig_pda`, oldest first, rebuilt from the memos of the squads
+/// instructions in the transactions touching the multisig account. Only the latest `limit` transactions
+/// are read when set. Failed transactions are skipped, they changed nothing on chain
+pub async fn get_audit_entries(rpc_client: &RpcClient, multisig_pda: &Pubkey, limit: Option<usize>) -> Result<Vec<AuditEntry>, AuditError> {
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let remaining = limit.map(|limit| limit - signatures.len()).unwrap_or(SIGNATURES_PAGE_SIZE);
+        if remaining == 0 {
+            break;
+        }
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(remaining.min(SIGNATURES_PAGE_SIZE)),
+            commitment: Some(rpc_client.commitment()),
+        };
+        let page = rpc_client
+            .get_signatures_for_address_with_config(multisig_pda, config)
+            .await
+            .map_err(|_| AuditError::FailedToFetchSignatures(*multisig_pda))?;
+
+        let is_last_page = page.len() < SIGNATURES_PAGE_SIZE;
+        for status in page {
+            let signature = Signature::from_str(&status.signature).map_err(|_| AuditError::FailedToFetchSignatures(*multisig_pda))?;
+            before = Some(signature);
+            signatures.push((signature, status.err.is_none()));
+        }
+
+        if is_last_page {
+            break;
+        }
+    }
+
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(rpc_client.commitment()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    let mut entries = Vec::new();
+    // Signatures come newest first
+    for (signature, is_success) in signatures.into_iter().rev() {
+        if !is_success {
+            continue;
+        }
+
+        let transaction = rpc_client
+            .get_transaction_with_config(&signature, config)
+            .await
+            .map_err(|_| AuditError::FailedToFetchTransaction(signature))?;
+        let decoded = transaction
+            .transaction
+            .transaction
+            .decode()
+            .ok_or(AuditError::FailedToDecodeTransaction(signature))?;
+
+        entries.extend(decode_audit_entries(multisig_pda, signature, transaction.slot, transaction.block_time, &decoded.message));
+    }
+
+    Ok(entries)
+}
+
+/// Platform memos of the squads instructions of `message` acting on `multisig_pda`
+pub fn decode_audit_entries(
+    multisig_pda: &Pubkey,
+    signature: Signature,
+    slot: u64,
+    block_time: Option<i64>,
+    message: &VersionedMessage,
+) -> Vec<AuditEntry> {
+    let account_keys = message.static_account_keys();
+    let Some(fee_payer) = account_keys.first() else {
+        return Vec::new();
+    };
+
+    message
+        .instructions()
+        .iter()
+        .filter(|instruction| account_keys.get(instruction.program_id_index as usize) == Some(&squads_multisig_program::ID))
+        .filter(|instruction| {
+            instruction
+                .accounts
+                .iter()
+                .any(|index| account_keys.get(*index as usize) == Some(multisig_pda))
+        })
+        .filter_map(|instruction| decode_audit_memo(&instruction.data))
+        .map(|(instruction, memo)| AuditEntry {
+            signature,
+            slot,
+            block_time,
+            instruction,
+            fee_payer: *fee_payer,
+            memo,
+        })
+        .collect()
+}
+
+/// Name and platform memo of a squads instruction, `None` for instructions without one
+pub fn decode_audit_memo(data: &[u8]) -> Option<(&'static str, AuditMemo)> {
+    let discriminator: [u8; 8] = data.get(..8)?.try_into().ok()?;
+    let mut args = &data[8..];
+
+    let (name, memo) = if discriminator == squads_instruction::MultisigCreateV2::DISCRIMINATOR {
+        ("MultisigCreateV2", squads_instruction::MultisigCreateV2::deserialize(&mut args).ok()?.args.memo)
+    } else if discriminator == squads_instruction::ConfigTransactionCreate::DISCRIMINATOR {
+        ("ConfigTransactionCreate", squads_instruction::ConfigTransactionCreate::deserialize(&mut args).ok()?.args.memo)
+    } else if discriminator == squads_instruction::VaultTransactionCreate::DISCRIMINATOR {
+        ("VaultTransactionCreate", squads_instruction::VaultTransactionCreate::deserialize(&mut args).ok()?.args.memo)
+    } else if discriminator == squads_discriminator("vault_transaction_create_from_buffer") {
+        ("VaultTransactionCreateFromBuffer", VaultTransactionCreateArgs::deserialize(&mut args).ok()?.memo)
+    } else if discriminator == squads_instruction::ProposalApprove::DISCRIMINATOR {
+        ("ProposalApprove", squads_instruction::ProposalApprove::deserialize(&mut args).ok()?.args.memo)
+    } else if discriminator == squads_instruction::ProposalReject::DISCRIMINATOR {
+        ("ProposalReject", squads_instruction::ProposalReject::deserialize(&mut args).ok()?.args.memo)
+    } else if discriminator == squads_instruction::ProposalCancel::DISCRIMINATOR {
+        ("ProposalCancel", squads_instruction::ProposalCancel::deserialize(&mut args).ok()?.args.memo)
+    } else if discriminator == squads_instruction::SpendingLimitUse::DISCRIMINATOR {
+        ("SpendingLimitUse", squads_instruction::SpendingLimitUse::deserialize(&mut args).ok()?.args.memo)
+    } else {
+        return None;
+    };
+
+    AuditMemo::decode(&memo?).map(|memo| (name, memo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::message::Message;
+    use squads_multisig::client::{proposal_approve, ProposalVoteAccounts};
+    use squads_multisig::squads_multisig_program::ProposalVoteArgs;
+    use crate::domain::entities::memo::audit_memo::{audit_memo, with_audit_context, AuditContext};
+
+    fn vote(multisig_pda: Pubkey, member: Pubkey, memo: Option<String>) -> Message {
+        let ix = proposal_approve(
+            ProposalVoteAccounts { multisig: multisig_pda, member, proposal: Pubkey::new_unique() },
+            ProposalVoteArgs { memo },
+            Some(squads_multisig_program::ID),
+        );
+
+        Message::new(&[ix], Some(&member))
+    }
+
+    #[tokio::test]
+    async fn decodes_platform_memo_of_vote() {
+        let multisig_pda = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+        let context = AuditContext { project_id: Some("7".to_string()), proposal_id: Some("42".to_string()), ..AuditContext::default() };
+        let memo = with_audit_context(context.clone(), async { audit_memo("Approve transaction 3") }).await;
+        let message = VersionedMessage::Legacy(vote(multisig_pda, member, memo));
+
+        let entries = decode_audit_entries(&multisig_pda, Signature::default(), 9, Some(1718000000), &message);
+
+        assert_eq!(1, entries.len());
+        assert_eq!("ProposalApprove", entries[0].instruction);
+        assert_eq!(member, entries[0].fee_payer);
+        assert_eq!(AuditMemo { context, action: "Approve transaction 3".to_string() }, entries[0].memo);
+        assert_eq!("42", entries[0].to_json()["proposal_id"]);
+    }
+
+    #[test]
+    fn skips_other_memos_and_other_multisigs() {
+        let multisig_pda = Pubkey::new_unique();
+        let member = Pubkey::new_unique();
+
+        let free_text = VersionedMessage::Legacy(vote(multisig_pda, member, Some("Approve".to_string())));
+        let other_multisig = VersionedMessage::Legacy(vote(Pubkey::new_unique(), member, audit_memo("Approve")));
+
+        assert!(decode_audit_entries(&multisig_pda, Signature::default(), 9, None, &free_text).is_empty());
+        assert!(decode_audit_entries(&multisig_pda, Signature::default(), 9, None, &other_multisig).is_empty());
+    }
+}
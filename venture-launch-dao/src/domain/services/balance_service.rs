@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::{Response, RpcKeyedAccount};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::domain::entities::multisig::business_analyst_multisig_trait::NATIVE_SOL_DECIMALS;
+use crate::domain::entities::token::amount::format_ui_amount;
+use crate::domain::entities::token::mint::{unpack_token_account, MintInfo};
+
+use super::error::BalanceError;
+
+/// One token account of an owner, with what it takes to show the amount in whole tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub token_account: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub decimals: u8,
+    pub amount: u64,
+}
+
+impl TokenBalance {
+    pub fn get_ui_amount(&self) -> String {
+        format_ui_amount(self.amount, self.decimals)
+    }
+}
+
+/// Lamports of an owner and every token account it owns under either token program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Balances {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub tokens: Vec<TokenBalance>,
+}
+
+impl Balances {
+    /// Everything the owner holds of `mint`, over all of its token accounts of the mint
+    pub fn get_token_amount(&self, mint: &Pubkey) -> u64 {
+        self.tokens
+            .iter()
+            .filter(|token| token.mint == *mint)
+            .map(|token| token.amount)
+            .sum()
+    }
+
+    pub fn get_ui_lamports(&self) -> String {
+        format_ui_amount(self.lamports, NATIVE_SOL_DECIMALS)
+    }
+
+    pub fn to_json(&self) -> String {
+        let tokens: Vec<String> = self.tokens
+            .iter()
+            .map(|token| format!(
+                "{{\"token_account\":  \"{}\", \"mint\":  \"{}\", \"token_program\":  \"{}\", \"decimals\":  {}, \"amount\":  \"{}\", \"ui_amount\":  \"{}\"}}",
+                token.token_account, token.mint, token.token_program, token.decimals, token.amount, token.get_ui_amount()
+            ))
+            .collect();
+
+        format!(
+            "\"owner\":  \"{}\",
+            \"lamports\":  \"{}\",
+            \"sol\":  \"{}\",
+            \"tokens\":  [{}]",
+            self.owner, self.lamports, self.get_ui_lamports(), tokens.join(", ")
+        )
+    }
+}
+
+/// Lamports and token accounts of `owner`, token accounts sorted by mint and then by address
+pub async fn get_balances(rpc_client: &RpcClient, owner: &Pubkey) -> Result<Balances, BalanceError> {
+    let lamports = rpc_client
+        .get_balance(owner)
+        .await
+        .map_err(|_| BalanceError::FailedToFetchBalance(*owner))?;
+
+    let mut token_accounts = get_token_accounts(rpc_client, owner, &spl_token::id()).await?;
+    token_accounts.extend(get_token_accounts(rpc_client, owner, &spl_token_2022::id()).await?);
+
+    let mut mints: Vec<Pubkey> = token_accounts.iter().map(|(_, mint, _)| *mint).collect();
+    mints.sort();
+    mints.dedup();
+    let mint_infos = get_mint_infos(rpc_client, &mints).await?;
+
+    Ok(Balances { owner: *owner, lamports, tokens: get_token_balances(token_accounts, &mint_infos)? })
+}
+
+/// Token accounts of `owner` under `token_program` as address, mint and amount
+async fn get_token_accounts(rpc_client: &RpcClient, owner: &Pubkey, token_program: &Pubkey) -> Result<Vec<(Pubkey, Pubkey, u64)>, BalanceError> {
+    let failed = || BalanceError::FailedToFetchTokenAccounts { owner: *owner, token_program: *token_program };
+
+    // `get_token_accounts_by_owner` only asks for parsed JSON, raw accounts unpack the same way for both programs
+    let response: Response<Vec<RpcKeyedAccount>> = rpc_client
+        .send(
+            RpcRequest::GetTokenAccountsByOwner,
+            json!([
+                owner.to_string(),
+                { "programId": token_program.to_string() },
+                { "encoding": "base64", "commitment": rpc_client.commitment().commitment }
+            ]),
+        )
+        .await
+        .map_err(|_| failed())?;
+
+    response
+        .value
+        .into_iter()
+        .map(|keyed_account| {
+            let address = Pubkey::from_str(&keyed_account.pubkey).map_err(|_| failed())?;
+            let account: Account = keyed_account.account.decode().ok_or_else(failed)?;
+            let token_account = unpack_token_account(&address, &account).map_err(|_| failed())?;
+
+            Ok((address, token_account.mint, token_account.amount))
+        })
+        .collect()
+}
+
+async fn get_mint_infos(rpc_client: &RpcClient, mints: &[Pubkey]) -> Result<HashMap<Pubkey, MintInfo>, BalanceError> {
+    let mut mint_infos = HashMap::with_capacity(mints.len());
+    for chunk in mints.chunks(100) {
+        let accounts = rpc_client
+            .get_multiple_accounts(chunk)
+            .await
+            .map_err(|_| BalanceError::FailedToFetchMints)?;
+
+        for (mint, account) in chunk.iter().zip(accounts) {
+            let account = account.ok_or(BalanceError::InvalidMint(*mint))?;
+            let mint_info = MintInfo::from_account(mint, &account).map_err(|_| BalanceError::InvalidMint(*mint))?;
+            mint_infos.insert(*mint, mint_info);
+        }
+    }
+
+    Ok(mint_infos)
+}
+
+fn get_token_balances(token_accounts: Vec<(Pubkey, Pubkey, u64)>, mint_infos: &HashMap<Pubkey, MintInfo>) -> Result<Vec<TokenBalance>, BalanceError> {
+    let mut tokens = token_accounts
+        .into_iter()
+        .map(|(token_account, mint, amount)| {
+            let mint_info = mint_infos.get(&mint).ok_or(BalanceError::InvalidMint(mint))?;
+
+            Ok(TokenBalance {
+                token_account,
+                mint,
+                token_program: mint_info.token_program,
+                decimals: mint_info.decimals,
+                amount,
+            })
+        })
+        .collect::<Result<Vec<TokenBalance>, BalanceError>>()?;
+
+    tokens.sort_by(|a, b| a.mint.cmp(&b.mint).then_with(|| a.token_account.cmp(&b.token_account)));
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mint_info(mint: Pubkey, token_program: Pubkey, decimals: u8) -> MintInfo {
+        MintInfo {
+            mint,
+            token_program,
+            decimals,
+            transfer_fee: None,
+            transfer_hook_program: None,
+            token_account_len: 165,
+        }
+    }
+
+    #[test]
+    fn token_balances_carry_decimals_of_their_mint() {
+        let legacy_mint = Pubkey::new_from_array([2; 32]);
+        let token_2022_mint = Pubkey::new_from_array([1; 32]);
+        let mint_infos = HashMap::from([
+            (legacy_mint, mint_info(legacy_mint, spl_token::id(), 6)),
+            (token_2022_mint, mint_info(token_2022_mint, spl_token_2022::id(), 9)),
+        ]);
+        let legacy_accounts = [Pubkey::new_from_array([4; 32]), Pubkey::new_from_array([3; 32])];
+        let token_2022_account = Pubkey::new_from_array([5; 32]);
+
+        let tokens = get_token_balances(
+            vec![
+                (legacy_accounts[0], legacy_mint, 1_500_000),
+                (token_2022_account, token_2022_mint, 2_000_000_000),
+                (legacy_accounts[1], legacy_mint, 250_000),
+            ],
+            &mint_infos,
+        )
+        .unwrap();
+
+        assert_eq!(vec![token_2022_account, legacy_accounts[1], legacy_accounts[0]], tokens.iter().map(|token| token.token_account).collect::<Vec<Pubkey>>());
+        assert_eq!(spl_token_2022::id(), tokens[0].token_program);
+        assert_eq!("2", tokens[0].get_ui_amount());
+        assert_eq!("1.5", tokens[2].get_ui_amount());
+
+        let balances = Balances { owner: Pubkey::new_unique(), lamports: 2_500_000_000, tokens };
+        assert_eq!(1_750_000, balances.get_token_amount(&legacy_mint));
+        assert_eq!(0, balances.get_token_amount(&Pubkey::new_unique()));
+        assert_eq!("2.5", balances.get_ui_lamports());
+    }
+
+    #[test]
+    fn rejects_token_accounts_of_unknown_mints() {
+        let mint = Pubkey::new_unique();
+
+        assert!(matches!(
+            get_token_balances(vec![(Pubkey::new_unique(), mint, 1)], &HashMap::new()),
+            Err(BalanceError::InvalidMint(unknown)) if unknown == mint
+        ));
+    }
+}
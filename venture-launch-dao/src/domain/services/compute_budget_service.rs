@@ -0,0 +1,298 @@
+use dotenv::dotenv;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+
+use super::error::PreflightError;
+
+/// Units requested when simulation did not report what the transaction consumes
+pub const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+/// Highest limit a single transaction may request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+/// Units taken by the two compute budget instructions themselves
+const COMPUTE_BUDGET_INSTRUCTIONS_UNITS: u32 = 300;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitLimitStrategy {
+    Fixed(u32),
+    /// Units consumed in simulation times `margin_percent` / 100
+    Simulated { margin_percent: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitPriceStrategy {
+    /// Micro-lamports per compute unit
+    Fixed(u64),
+    /// Percentile of the recent prioritization fees paid for the writable accounts of the transaction
+    Percentile { percentile: u8, max_micro_lamports: Option<u64> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeBudgetConfig {
+    pub unit_limit: UnitLimitStrategy,
+    pub unit_price: UnitPriceStrategy,
+}
+
+impl Default for ComputeBudgetConfig {
+    fn default() -> Self {
+        Self {
+            unit_limit: UnitLimitStrategy::Simulated { margin_percent: 120 },
+            unit_price: UnitPriceStrategy::Percentile { percentile: 75, max_micro_lamports: None },
+        }
+    }
+}
+
+impl ComputeBudgetConfig {
+    /// Reads `COMPUTE_UNIT_LIMIT` (`fixed:<units>` or `simulated:<margin percent>`),
+    /// `COMPUTE_UNIT_PRICE` (`fixed:<micro-lamports>` or `percentile:<0-100>`)
+    /// and `COMPUTE_UNIT_PRICE_MAX`, falling back to the defaults for unset values
+    pub fn from_env() -> Result<Self, PreflightError> {
+        dotenv().ok();
+
+        let default = Self::default();
+
+        let unit_limit = match std::env::var("COMPUTE_UNIT_LIMIT") {
+            Ok(value) => parse_unit_limit(&value)?,
+            Err(_) => default.unit_limit
+        };
+
+        let max_micro_lamports = match std::env::var("COMPUTE_UNIT_PRICE_MAX") {
+            Ok(value) => Some(value.parse::<u64>().map_err(|_| PreflightError::InvalidComputeBudgetConfig(value))?),
+            Err(_) => None
+        };
+
+        let unit_price = match std::env::var("COMPUTE_UNIT_PRICE") {
+            Ok(value) => parse_unit_price(&value, max_micro_lamports)?,
+            Err(_) => match default.unit_price {
+                UnitPriceStrategy::Percentile { percentile, .. } => UnitPriceStrategy::Percentile { percentile, max_micro_lamports },
+                fixed => fixed
+            }
+        };
+
+        Ok(Self { unit_limit, unit_price })
+    }
+}
+
+fn parse_unit_limit(value: &str) -> Result<UnitLimitStrategy, PreflightError> {
+    let invalid = || PreflightError::InvalidComputeBudgetConfig(value.to_string());
+
+    match value.split_once(':') {
+        Some(("fixed", units)) => {
+            let units = units.parse::<u32>().map_err(|_| invalid())?;
+            if units == 0 || units > MAX_COMPUTE_UNIT_LIMIT {
+                return Err(invalid());
+            }
+            Ok(UnitLimitStrategy::Fixed(units))
+        },
+        Some(("simulated", margin_percent)) => {
+            let margin_percent = margin_percent.parse::<u32>().map_err(|_| invalid())?;
+            if margin_percent < 100 {
+                return Err(invalid());
+            }
+            Ok(UnitLimitStrategy::Simulated { margin_percent })
+        },
+        _ => Err(invalid())
+    }
+}
+
+fn parse_unit_price(value: &str, max_micro_lamports: Option<u64>) -> Result<UnitPriceStrategy, PreflightError> {
+    let invalid = || PreflightError::InvalidComputeBudgetConfig(value.to_string());
+
+    match value.split_once(':') {
+        Some(("fixed", micro_lamports)) => Ok(UnitPriceStrategy::Fixed(micro_lamports.parse::<u64>().map_err(|_| invalid())?)),
+        Some(("percentile", percentile)) => {
+            let percentile = percentile.parse::<u8>().map_err(|_| invalid())?;
+            if percentile > 100 {
+                return Err(invalid());
+            }
+            Ok(UnitPriceStrategy::Percentile { percentile, max_micro_lamports })
+        },
+        _ => Err(invalid())
+    }
+}
+
+/// Compute budget picked for one transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub unit_limit: u32,
+    pub unit_price: u64,
+}
+
+impl ComputeBudget {
+    /// Lamports paid on top of the signature fees, the whole limit is charged even if fewer units are used
+    pub fn priority_fee(&self) -> u64 {
+        let micro_lamports = self.unit_limit as u128 * self.unit_price as u128;
+        micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64
+    }
+
+    pub fn instructions(&self) -> Vec<Instruction> {
+        vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(self.unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(self.unit_price),
+        ]
+    }
+}
+
+/// Value at `percentile` of `fees`, nearest-rank, 0 for no fees
+pub fn percentile_fee(fees: &[u64], percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+
+    let mut fees = fees.to_vec();
+    fees.sort_unstable();
+
+    let rank = (fees.len() * percentile.min(100) as usize).div_ceil(100);
+    fees[rank.saturating_sub(1)]
+}
+
+pub fn simulated_unit_limit(units_consumed: Option<u64>, margin_percent: u32) -> u32 {
+    let units = match units_consumed {
+        Some(units) => units + COMPUTE_BUDGET_INSTRUCTIONS_UNITS as u64,
+        None => return DEFAULT_COMPUTE_UNIT_LIMIT
+    };
+
+    let units = units * margin_percent as u64 / 100;
+    units.min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+}
+
+/// Picks the compute budget of `tx` according to `config`.
+/// `units_consumed` comes from the preflight simulation of the transaction without a budget
+pub async fn estimate_compute_budget(
+    rpc_client: &RpcClient,
+    config: &ComputeBudgetConfig,
+    tx: &Transaction,
+    units_consumed: Option<u64>
+) -> Result<ComputeBudget, PreflightError> {
+    let unit_limit = match config.unit_limit {
+        UnitLimitStrategy::Fixed(units) => units,
+        UnitLimitStrategy::Simulated { margin_percent } => simulated_unit_limit(units_consumed, margin_percent),
+    };
+
+    let unit_price = match config.unit_price {
+        UnitPriceStrategy::Fixed(micro_lamports) => micro_lamports,
+        UnitPriceStrategy::Percentile { percentile, max_micro_lamports } => {
+            let writable_accounts: Vec<Pubkey> = tx
+                .message
+                .account_keys
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| tx.message.is_writable(*index))
+                .map(|(_, pubkey)| *pubkey)
+                .collect();
+
+            let fees = rpc_client
+                .get_recent_prioritization_fees(&writable_accounts)
+                .await
+                .map_err(|_| PreflightError::FailedToFetchPrioritizationFees)?
+                .into_iter()
+                .map(|fee| fee.prioritization_fee)
+                .collect::<Vec<u64>>();
+
+            let fee = percentile_fee(&fees, percentile);
+            max_micro_lamports.map_or(fee, |max| fee.min(max))
+        },
+    };
+
+    Ok(ComputeBudget { unit_limit, unit_price })
+}
+
+/// Rebuilds the unsigned `tx` with the compute budget instructions in front,
+/// instructions of an earlier budget are dropped
+pub fn with_compute_budget(tx: &Transaction, budget: &ComputeBudget) -> Transaction {
+    let message = &tx.message;
+    let payer = message.account_keys[0];
+
+    let instructions = message
+        .instructions
+        .iter()
+        .filter(|instruction| message.account_keys[instruction.program_id_index as usize] != solana_sdk::compute_budget::id())
+        .map(|instruction| Instruction {
+            program_id: message.account_keys[instruction.program_id_index as usize],
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|index| {
+                    let index = *index as usize;
+                    AccountMeta {
+                        pubkey: message.account_keys[index],
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_writable(index),
+                    }
+                })
+                .collect(),
+            data: instruction.data.clone(),
+        });
+
+    let instructions: Vec<Instruction> = budget.instructions().into_iter().chain(instructions).collect();
+
+    Transaction::new_with_payer(&instructions, Some(&payer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{signature::Keypair, signer::Signer, system_instruction};
+
+    #[test]
+    fn priority_fee_rounds_up() {
+        assert_eq!(0, ComputeBudget { unit_limit: 200_000, unit_price: 0 }.priority_fee());
+        assert_eq!(1, ComputeBudget { unit_limit: 1, unit_price: 1 }.priority_fee());
+        assert_eq!(200, ComputeBudget { unit_limit: 200_000, unit_price: 1_000 }.priority_fee());
+    }
+
+    #[test]
+    fn percentile_fee_uses_nearest_rank() {
+        let fees = [50, 10, 40, 20, 30];
+
+        assert_eq!(0, percentile_fee(&[], 75));
+        assert_eq!(10, percentile_fee(&fees, 0));
+        assert_eq!(30, percentile_fee(&fees, 50));
+        assert_eq!(40, percentile_fee(&fees, 75));
+        assert_eq!(50, percentile_fee(&fees, 100));
+    }
+
+    #[test]
+    fn simulated_unit_limit_applies_margin_and_cap() {
+        assert_eq!(DEFAULT_COMPUTE_UNIT_LIMIT, simulated_unit_limit(None, 120));
+        assert_eq!(12_360, simulated_unit_limit(Some(10_000), 120));
+        assert_eq!(MAX_COMPUTE_UNIT_LIMIT, simulated_unit_limit(Some(1_300_000), 150));
+    }
+
+    #[test]
+    fn parses_strategies() {
+        assert_eq!(UnitLimitStrategy::Fixed(300_000), parse_unit_limit("fixed:300000").unwrap());
+        assert_eq!(UnitLimitStrategy::Simulated { margin_percent: 110 }, parse_unit_limit("simulated:110").unwrap());
+        assert!(parse_unit_limit("simulated:90").is_err());
+        assert!(parse_unit_limit("fixed:2000000").is_err());
+
+        assert_eq!(UnitPriceStrategy::Fixed(5_000), parse_unit_price("fixed:5000", None).unwrap());
+        assert_eq!(
+            UnitPriceStrategy::Percentile { percentile: 90, max_micro_lamports: Some(10) },
+            parse_unit_price("percentile:90", Some(10)).unwrap()
+        );
+        assert!(parse_unit_price("percentile:101", None).is_err());
+        assert!(parse_unit_price("median", None).is_err());
+    }
+
+    #[test]
+    fn compute_budget_is_prepended_once() {
+        let payer = Keypair::new();
+        let receiver = Pubkey::new_unique();
+        let tx = Transaction::new_with_payer(&[system_instruction::transfer(&payer.pubkey(), &receiver, 1)], Some(&payer.pubkey()));
+
+        let budget = ComputeBudget { unit_limit: 1_000, unit_price: 10 };
+        let tx = with_compute_budget(&tx, &budget);
+        let tx = with_compute_budget(&tx, &ComputeBudget { unit_limit: 2_000, unit_price: 20 });
+
+        assert_eq!(3, tx.message.instructions.len());
+        assert_eq!(payer.pubkey(), tx.message.account_keys[0]);
+        assert_eq!(
+            ComputeBudgetInstruction::set_compute_unit_limit(2_000).data,
+            tx.message.instructions[0].data
+        );
+    }
+}
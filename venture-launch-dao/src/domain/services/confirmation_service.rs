@@ -0,0 +1,314 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use dotenv::dotenv;
+use futures::StreamExt;
+use lazy_static::lazy_static;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSignatureSubscribeConfig;
+use solana_client::rpc_response::RpcSignatureResult;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::TransactionStatus;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use super::error::ConfirmationError;
+
+pub const DEFAULT_CONFIRM_TIMEOUT: Duration = Duration::from_millis(60_000);
+pub const DEFAULT_CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(2_000);
+
+lazy_static! {
+    static ref TRACKER: ConfirmationTracker = ConfirmationTracker::default();
+}
+
+/// Tracker shared by every request, all of them wait on the same websocket connection
+pub fn get_confirmation_tracker() -> &'static ConfirmationTracker {
+    &TRACKER
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmationConfig {
+    pub commitment: CommitmentConfig,
+    /// How long to wait before giving up on the signature
+    pub timeout: Duration,
+    /// How often the status is polled, the only source of the outcome when websockets are unavailable
+    pub poll_interval: Duration,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            commitment: CommitmentConfig::confirmed(),
+            timeout: DEFAULT_CONFIRM_TIMEOUT,
+            poll_interval: DEFAULT_CONFIRM_POLL_INTERVAL,
+        }
+    }
+}
+
+impl ConfirmationConfig {
+    /// Reads `CONFIRM_COMMITMENT`, `CONFIRM_TIMEOUT_MS` and `CONFIRM_POLL_INTERVAL_MS`, falling back to the defaults
+    pub fn from_env() -> Result<Self, ConfirmationError> {
+        dotenv().ok();
+
+        let default = Self::default();
+
+        let commitment = match std::env::var("CONFIRM_COMMITMENT") {
+            Ok(value) => parse_commitment(&value)?,
+            Err(_) => default.commitment
+        };
+
+        let timeout = match std::env::var("CONFIRM_TIMEOUT_MS") {
+            Ok(value) => Duration::from_millis(value.parse::<u64>().map_err(|_| ConfirmationError::InvalidConfirmationConfig(value))?),
+            Err(_) => default.timeout
+        };
+
+        let poll_interval = match std::env::var("CONFIRM_POLL_INTERVAL_MS") {
+            Ok(value) => Duration::from_millis(value.parse::<u64>().map_err(|_| ConfirmationError::InvalidConfirmationConfig(value))?),
+            Err(_) => default.poll_interval
+        };
+
+        Ok(Self { commitment, timeout, poll_interval })
+    }
+
+    /// Overrides the commitment and timeout with the ones a request asked for
+    pub fn with_request(self, commitment: Option<String>, timeout_ms: Option<u64>) -> Result<Self, ConfirmationError> {
+        let commitment = match commitment {
+            Some(value) => parse_commitment(&value)?,
+            None => self.commitment
+        };
+        let timeout = timeout_ms.map(Duration::from_millis).unwrap_or(self.timeout);
+
+        Ok(Self { commitment, timeout, ..self })
+    }
+}
+
+pub fn parse_commitment(value: &str) -> Result<CommitmentConfig, ConfirmationError> {
+    match value {
+        "processed" => Ok(CommitmentConfig::processed()),
+        "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        _ => Err(ConfirmationError::InvalidConfirmationConfig(value.to_string()))
+    }
+}
+
+/// Outcome of waiting for a signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirmation {
+    Confirmed { slot: Slot },
+    Failed { slot: Slot, err: String },
+    /// The signature did not reach the commitment before the timeout
+    TimedOut,
+}
+
+impl Confirmation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Confirmed { .. } => "landed",
+            Self::Failed { .. } => "failed",
+            Self::TimedOut => "timed_out",
+        }
+    }
+}
+
+fn confirmation_from_status(status: Option<&TransactionStatus>, commitment: CommitmentConfig) -> Option<Confirmation> {
+    let status = status?;
+
+    match &status.err {
+        Some(err) => Some(Confirmation::Failed { slot: status.slot, err: err.to_string() }),
+        None if status.satisfies_commitment(commitment) => Some(Confirmation::Confirmed { slot: status.slot }),
+        None => None
+    }
+}
+
+/// Waits for signatures over `signature_subscribe`. The connection is opened by the first request that
+/// needs it, shared by all requests in flight and opened again by the next request once it fails
+#[derive(Default)]
+pub struct ConfirmationTracker {
+    pubsub: Mutex<Option<Arc<PubsubClient>>>,
+}
+
+impl ConfirmationTracker {
+    async fn get_pubsub(&self, rpc_url: &str) -> Option<Arc<PubsubClient>> {
+        let mut pubsub = self.pubsub.lock().await;
+        if let Some(pubsub) = pubsub.as_ref() {
+            return Some(pubsub.clone());
+        }
+
+        let ws_url = get_ws_url(rpc_url);
+        match PubsubClient::new(&ws_url).await {
+            Ok(client) => {
+                let client = Arc::new(client);
+                *pubsub = Some(client.clone());
+                Some(client)
+            },
+            Err(_) => None
+        }
+    }
+
+    /// Drops `failed` unless another request already replaced it, logged once per failed connection
+    async fn reset(&self, failed: &Arc<PubsubClient>) {
+        let mut pubsub = self.pubsub.lock().await;
+        if pubsub.as_ref().is_some_and(|pubsub| Arc::ptr_eq(pubsub, failed)) {
+            *pubsub = None;
+            println!("[{:?} CONFIRMATION INFO] websocket failed, polling until the next request reconnects", chrono::Utc::now());
+        }
+    }
+
+    /// Waits until `signature` reaches the commitment of `config`, fails or `config.timeout` passes.
+    /// The status is polled every `config.poll_interval` next to the subscription, it catches a signature
+    /// processed before the subscription was set up and keeps working when the websocket goes away
+    pub async fn wait_for_confirmation(
+        &self,
+        rpc_client: &RpcClient,
+        signature: &Signature,
+        config: &ConfirmationConfig
+    ) -> Result<Confirmation, ConfirmationError> {
+        let deadline = Instant::now() + config.timeout;
+        let subscribe_config = RpcSignatureSubscribeConfig {
+            commitment: Some(config.commitment),
+            enable_received_notification: Some(false),
+        };
+
+        let pubsub = self.get_pubsub(&rpc_client.url()).await;
+        let subscription = match &pubsub {
+            Some(pubsub) => match pubsub.signature_subscribe(signature, Some(subscribe_config)).await {
+                Ok(subscription) => Some(subscription),
+                Err(_) => {
+                    self.reset(pubsub).await;
+                    None
+                }
+            },
+            None => None
+        };
+        let (mut notifications, unsubscribe) = match subscription {
+            Some((notifications, unsubscribe)) => (Some(notifications), Some(unsubscribe)),
+            None => (None, None)
+        };
+
+        let confirmation = loop {
+            let status = match rpc_client.get_signature_statuses(&[*signature]).await {
+                Ok(response) => response.value.into_iter().next().flatten(),
+                Err(_) => break Err(ConfirmationError::FailedToFetchStatus(*signature))
+            };
+            if let Some(confirmation) = confirmation_from_status(status.as_ref(), config.commitment) {
+                break Ok(confirmation);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                break Ok(Confirmation::TimedOut);
+            }
+            let wait = (deadline - now).min(config.poll_interval);
+
+            let notification = match notifications.as_mut() {
+                Some(notifications) => tokio::time::timeout(wait, notifications.next()).await.ok(),
+                None => {
+                    tokio::time::sleep(wait).await;
+                    None
+                }
+            };
+
+            match notification {
+                Some(Some(response)) => {
+                    if let RpcSignatureResult::ProcessedSignature(result) = response.value {
+                        let slot = response.context.slot;
+                        break Ok(match result.err {
+                            Some(err) => Confirmation::Failed { slot, err: err.to_string() },
+                            None => Confirmation::Confirmed { slot }
+                        });
+                    }
+                },
+                // The connection closed, the next request opens a new one and this one keeps polling
+                Some(None) => {
+                    notifications = None;
+                    if let Some(pubsub) = &pubsub {
+                        self.reset(pubsub).await;
+                    }
+                },
+                None => {}
+            }
+        };
+
+        drop(notifications);
+        if let Some(unsubscribe) = unsubscribe {
+            unsubscribe().await;
+        }
+
+        confirmation
+    }
+}
+
+/// `DEFAULT_WS_CLIENT`, or the websocket endpoint a local validator serves next to `rpc_url`
+pub fn get_ws_url(rpc_url: &str) -> String {
+    dotenv().ok();
+
+    if let Ok(ws_url) = std::env::var("DEFAULT_WS_CLIENT") {
+        return ws_url;
+    }
+
+    let ws_url = rpc_url.replacen("https://", "wss://", 1).replacen("http://", "ws://", 1);
+
+    // solana-test-validator serves websockets one port above rpc
+    ws_url.replace(":8899", ":8900")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::transaction::TransactionError;
+    use solana_transaction_status::TransactionConfirmationStatus;
+
+    fn status(confirmation_status: TransactionConfirmationStatus, err: Option<TransactionError>) -> TransactionStatus {
+        TransactionStatus {
+            slot: 42,
+            confirmations: match confirmation_status {
+                TransactionConfirmationStatus::Finalized => None,
+                _ => Some(1),
+            },
+            status: match &err {
+                Some(err) => Err(err.clone()),
+                None => Ok(())
+            },
+            err,
+            confirmation_status: Some(confirmation_status),
+        }
+    }
+
+    #[test]
+    fn status_is_confirmed_at_commitment() {
+        let confirmed = status(TransactionConfirmationStatus::Confirmed, None);
+
+        assert_eq!(None, confirmation_from_status(None, CommitmentConfig::processed()));
+        assert_eq!(
+            Some(Confirmation::Confirmed { slot: 42 }),
+            confirmation_from_status(Some(&confirmed), CommitmentConfig::confirmed())
+        );
+        assert_eq!(None, confirmation_from_status(Some(&confirmed), CommitmentConfig::finalized()));
+    }
+
+    #[test]
+    fn failed_status_is_reported_at_any_commitment() {
+        let failed = status(TransactionConfirmationStatus::Processed, Some(TransactionError::AccountNotFound));
+
+        match confirmation_from_status(Some(&failed), CommitmentConfig::finalized()) {
+            Some(Confirmation::Failed { slot, .. }) => assert_eq!(42, slot),
+            confirmation => panic!("unexpected {confirmation:?}"),
+        }
+    }
+
+    #[test]
+    fn request_overrides_commitment_and_timeout() {
+        let config = ConfirmationConfig::default()
+            .with_request(Some("finalized".to_string()), Some(5_000))
+            .unwrap();
+
+        assert_eq!(CommitmentConfig::finalized(), config.commitment);
+        assert_eq!(Duration::from_millis(5_000), config.timeout);
+        assert_eq!(DEFAULT_CONFIRM_POLL_INTERVAL, config.poll_interval);
+        assert_eq!(ConfirmationConfig::default(), ConfirmationConfig::default().with_request(None, None).unwrap());
+        assert!(ConfirmationConfig::default().with_request(Some("max".to_string()), None).is_err());
+    }
+}
@@ -2,23 +2,50 @@ use std::error::Error;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use dotenv::dotenv;
+use serde::Deserialize;
 
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::signer::Signer;
 use solana_sdk::transaction::Transaction;
+use solana_sdk::program_pack::Pack;
 use spl_associated_token_account::get_associated_token_address;
-use spl_associated_token_account::instruction::create_associated_token_account;
-use squads_multisig::state::{Member, Permission, Permissions};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use squads_multisig::squads_multisig_program::{Multisig, Period};
+use squads_multisig::state::{ConfigAction, Member, Permission, Permissions};
 
 
+use crate::contract_module::associated_token::native::{get_wrapped_sol_address, get_wrapped_sol_balance, instruction_close_wrapped_sol_account, instruction_unwrap_sol, instructions_wrap_sol};
+use crate::contract_module::state::CryptoTracker;
+use crate::contract_module::venture_launch::{get_program_id, VentureLaunch};
+use crate::domain::entities::milestone::error::MilestoneError;
+use crate::domain::entities::milestone::entity::{Milestone, MilestoneStatus};
 use crate::domain::entities::multisig::base_multisig::{BaseMultisig, BaseMultisigCreateArgs, BaseMultisigInitArgs};
 use crate::domain::entities::multisig::base_multisig_trait::BaseMultisigTrait;
-use crate::domain::entities::multisig::business_analyst_multisig_trait::BusinessAnalystMultisigTrait;
+use crate::domain::entities::multisig::business_analyst_multisig_trait::{fits_in_transaction, get_multisig_spending_limit_pda, get_transaction_buffer_pda, AddSpendingLimitArgs, BusinessAnalystMultisigTrait, VENTURE_LAUNCH_CREATE_VAULT_EPHEMERAL_SIGNERS};
 use crate::domain::entities::multisig::investor_multisig_trait::InvestorMultisigTrait;
+use crate::domain::entities::token::amount::{format_ui_amount, TokenAmount};
+use crate::domain::entities::token::mint::{get_epoch, unpack_token_account, MintInfo};
+use crate::domain::services::audit_service::get_audit_entries;
+use crate::domain::services::compute_budget_service::{estimate_compute_budget, with_compute_budget, ComputeBudget, ComputeBudgetConfig};
+use crate::domain::services::confirmation_service::{get_confirmation_tracker, Confirmation, ConfirmationConfig};
+use crate::domain::services::dry_run_service::dry_run;
+use crate::domain::services::milestone_service::MilestoneStore;
+use crate::domain::services::nonce_service::{check_nonce_transaction, get_nonce, get_nonce_account_address, get_nonce_transaction, instruction_close_nonce_account, instruction_rotate_nonce_account, instructions_create_nonce_account};
+use crate::domain::services::error::{EscrowError, PreflightError, RefundError, RentError};
+use crate::domain::services::preflight_service::{check_member_permission, check_vault_token_balance, preflight_transaction};
+use crate::domain::services::refund_service::{get_snapshot_weights, split_pro_rata, RefundShare, REFUND_INVESTORS_PER_TRANSACTION};
+use crate::domain::services::rent_service::{get_closable_transactions, TransactionKind, CLOSES_PER_TRANSACTION};
+use crate::domain::services::sender_service::{notify_submitted, send_transaction, SendStatus, SenderConfig, SentTransaction, Submission};
+use crate::domain::services::store_service::{get_store, record_dao, record_proposal, record_signature};
+
+/// Transaction of a request together with the keypairs that have to sign it
+type Step<'a> = (Transaction, Vec<&'a Keypair>);
 
 async fn get_ba_keypair() -> Result<Keypair, String> {
     dotenv().ok();
@@ -32,7 +59,7 @@ async fn get_ba_keypair() -> Result<Keypair, String> {
 
     Ok(creator_keypair)
 }
-fn get_rpc_client() -> Result<RpcClient, String> {
+pub fn get_rpc_client() -> Result<RpcClient, String> {
     dotenv().ok();
 
     Ok(RpcClient::new(std::env::var("DEFAULT_RPC_CLIENT").unwrap_or_else(|_| "http://127.0.0.1:8899".into()).to_string()))
@@ -43,42 +70,157 @@ async fn create_base_multisig(create_key: &Keypair) -> Result<BaseMultisig, Stri
     let creator_keypair: Keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
 
     println!("creator: {}", creator_keypair.pubkey());
-    println!("balance: {}", rpc_client.get_balance(&creator_keypair.pubkey()).await.unwrap());
     let multisig = BaseMultisig::new(BaseMultisigCreateArgs{
         rpc_client,
         multisig_create_keypair: create_key.insecure_clone(),
         creator: creator_keypair.pubkey()
     }).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
 
-    let token_program_id = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
-    let token_mint = Pubkey::from_str("Gh9ZwEmdLJ8DscKNTkTqPbNwLNNBjuSzaG9Vp2KGtKJr").unwrap();
+    Ok(multisig)
+}
 
-    println!("{token_program_id}");
-    println!("{token_mint}");
+pub fn get_vault_token_mint() -> Pubkey {
+    solana_sdk::pubkey!("Gh9ZwEmdLJ8DscKNTkTqPbNwLNNBjuSzaG9Vp2KGtKJr")
+}
 
-    let ix = create_associated_token_account(
-        &creator_keypair.pubkey(),
-        &multisig.get_vault_pda(),
-        &token_mint,
-        &token_program_id
-    );
+/// Mints every DAO vault gets a token account for, the vault token mint followed by the comma separated `VAULT_TOKEN_MINTS`
+pub fn get_vault_token_mints() -> Result<Vec<Pubkey>, String> {
+    dotenv().ok();
 
-    let mut transaction = Transaction::new_with_payer(
-        &[ix],
-        Some(&creator_keypair.pubkey()),
-    );
+    let mut mints = vec![get_vault_token_mint()];
+    for mint in std::env::var("VAULT_TOKEN_MINTS").unwrap_or_default().split(',').map(str::trim).filter(|mint| !mint.is_empty()) {
+        let mint = Pubkey::from_str(mint).map_err(|err| format!("VAULT_TOKEN_MINTS {mint}: {err}"))?;
+        if !mints.contains(&mint) {
+            mints.push(mint);
+        }
+    }
+
+    Ok(mints)
+}
+
+/// Signs `tx`, runs it through preflight, prepends the compute budget and hands it to the sender
+async fn sign_and_send(rpc_client: &RpcClient, tx: &mut Transaction, signers: &[&Keypair]) -> Result<(SentTransaction, ComputeBudget), String> {
+    let recent_blockhash = rpc_client.get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    tx.try_sign(signers, recent_blockhash).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let report = preflight_transaction(rpc_client, tx).await.map_err(|err| err.to_response())?;
+
+    let budget = apply_compute_budget(rpc_client, tx, signers, report.units_consumed).await?;
+
+    let config = SenderConfig::from_env().map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let sent = send_transaction(rpc_client, &config, tx, signers).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    Ok((sent, budget))
+}
 
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.unwrap();
-    let _ = transaction.try_sign(&[&creator_keypair], recent_blockhash);
-    let _ = multisig.get_rpc_client().send_and_confirm_transaction(&transaction).await.expect("Failed to send transaction");
+/// Replaces `tx` with a copy carrying the compute budget picked by the configured strategies and signs it again
+async fn apply_compute_budget(rpc_client: &RpcClient, tx: &mut Transaction, signers: &[&Keypair], units_consumed: Option<u64>) -> Result<ComputeBudget, String> {
+    let config = ComputeBudgetConfig::from_env().map_err(|err| err.to_response())?;
+    let budget = estimate_compute_budget(rpc_client, &config, tx, units_consumed).await.map_err(|err| err.to_response())?;
 
-    let associated_token_address = get_associated_token_address(&multisig.get_vault_pda(), &token_mint);
-    let account_info = multisig.get_rpc_client().get_account(&associated_token_address).await;
-    if let Ok(_) = account_info {
-        println!("Associated token account already exists: {}", associated_token_address);
+    let recent_blockhash = tx.message.recent_blockhash;
+    *tx = with_compute_budget(tx, &budget);
+    tx.try_sign(signers, recent_blockhash).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    Ok(budget)
+}
+
+/// Sends the steps of a request in order, in dry run mode only signs and simulates them.
+/// Returns the part of the response describing what happened to the transactions
+async fn submit(rpc_client: &RpcClient, steps: Vec<Step<'_>>, is_dry_run: bool) -> Result<String, String> {
+    let mut priority_fee = 0;
+
+    if is_dry_run {
+        let mut transactions = Vec::with_capacity(steps.len());
+        for (mut tx, signers) in steps {
+            let recent_blockhash = rpc_client.get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            tx.try_sign(&signers, recent_blockhash).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+            // Later steps depend on earlier ones and may not simulate alone, they get the default limit
+            let units_consumed = preflight_transaction(rpc_client, &tx).await.ok().and_then(|report| report.units_consumed);
+            let budget = apply_compute_budget(rpc_client, &mut tx, &signers, units_consumed).await?;
+            priority_fee += budget.priority_fee();
+
+            transactions.push(tx);
+        }
+
+        let report = dry_run(rpc_client, &transactions).await.map_err(|err| err.to_response())?;
+        return Ok(format!(
+            "{},
+            \"priority_fee\":  {}",
+            report.to_response(),
+            priority_fee
+        ));
     }
 
-    Ok(multisig)
+    let mut signatures = Vec::with_capacity(steps.len());
+    for (mut tx, signers) in steps {
+        let (sent, budget) = sign_and_send(rpc_client, &mut tx, &signers).await?;
+        record_signature(&sent.signature, sent.status.name()).await;
+        signatures.push(format!("\"{}\"", sent.signature));
+        priority_fee += budget.priority_fee();
+
+        // The remaining steps build on this one, stop and tell which step did not land
+        let msg = match &sent.status {
+            SendStatus::Landed { .. } => continue,
+            SendStatus::Failed { err, .. } => format!("Transaction {} failed: {err}", sent.signature),
+            SendStatus::Expired { last_error: Some(err) } => format!("Transaction {} expired: {err}", sent.signature),
+            SendStatus::Expired { last_error: None } => format!("Transaction {} expired", sent.signature)
+        };
+
+        return Err(format!(
+            "\"msg\":  {},
+            \"status\":  \"{}\",
+            \"signatures\":  [{}],
+            \"priority_fee\":  {}",
+            serde_json::to_string(&msg).unwrap_or_default(),
+            sent.status.name(),
+            signatures.join(", "),
+            priority_fee
+        ));
+    }
+
+    Ok(format!(
+        "\"status\":  \"landed\",
+        \"signatures\":  [{}],
+        \"priority_fee\":  {}",
+        signatures.join(", "),
+        priority_fee
+    ))
+}
+
+/// Steps creating a config transaction and then proposing, approving and executing it with the BA key,
+/// together with the index of the config transaction.
+/// The follow-up instructions target the index the config transaction is going to get,
+/// so they can be built before the first step lands
+async fn config_transaction_steps<'a>(
+    multisig: &dyn BusinessAnalystMultisigTrait,
+    creator_keypair: &'a Keypair,
+    ix_create: Instruction,
+    spending_limits: Vec<Pubkey>
+) -> Result<(Vec<Step<'a>>, u64), String> {
+    let creator = creator_keypair.pubkey();
+    let transaction_index = multisig.get_multisig_transaction_index().await.map_err(|err| format!("\"msg\": \"{err}\""))? + 1;
+
+    let tx_create = multisig.get_transaction_from_instructions(creator, &[ix_create]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let ix_prpose = multisig.instruction_proposal_create_for_index(creator, transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let ix_approve = multisig.instruction_proposal_approve_for_index(creator, transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let ix_exec = multisig.instruction_config_transaction_execute_for_index(creator, transaction_index, spending_limits).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let tx_execute = multisig.get_transaction_from_instructions(creator, &[ix_prpose, ix_approve, ix_exec]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    Ok((vec![
+        (tx_create, vec![creator_keypair]),
+        (tx_execute, vec![creator_keypair])
+    ], transaction_index))
+}
+
+fn check_member_permissions(multisig: &Multisig, member: &Pubkey, permissions: &[Permission]) -> Result<(), String> {
+    for permission in permissions {
+        check_member_permission(multisig, member, *permission).map_err(|err| err.to_response())?;
+    }
+
+    Ok(())
 }
 
 async fn get_base_multisig(multisig_pda: Pubkey) -> Result<BaseMultisig, String> {
@@ -111,16 +253,14 @@ pub async fn airdrop(
         "🚀Airdropping {} SOL to {} with sig {}",
         amount, address, sig
     );
-    loop {
-        let confirmed = rpc_client.confirm_transaction(&sig).await?;
-        if confirmed {
-            break;
-        }
+    let config = ConfirmationConfig::from_env()?;
+    match get_confirmation_tracker().wait_for_confirmation(rpc_client, &sig, &config).await? {
+        Confirmation::Confirmed { .. } => Ok(sig),
+        confirmation => Err(format!("Airdrop {sig} {}", confirmation.name()).into())
     }
-    Ok(sig)
 }
 
-pub async fn create_dao() -> Result<String, String> {
+pub async fn create_dao(is_dry_run: bool) -> Result<String, String> {
     let create_key = Keypair::new();
     let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
 
@@ -128,26 +268,64 @@ pub async fn create_dao() -> Result<String, String> {
 
     let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
 
-    let mut tx = multisig.transaction_create_multisig(&[], 1, 0, &create_key).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    println!("before try_sign");
-    let _ = tx.try_sign(&[&creator_keypair, &create_key], recent_blockhash);
-    let _ = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    println!("after confirm");
+    // Idempotent, so a token account someone already created for the vault does not fail the request
+    let mut vault_token_mints = Vec::new();
+    for mint in get_vault_token_mints().map_err(|err| format!("\"msg\": \"{err}\""))? {
+        vault_token_mints.push(MintInfo::fetch(multisig.get_rpc_client(), &mint).await.map_err(|err| format!("\"msg\": \"{err}\""))?);
+    }
+    let mut vault_ata_groups: Vec<Vec<Instruction>> = vec![Vec::new()];
+    for mint_info in &vault_token_mints {
+        let ix_vault_ata = create_associated_token_account_idempotent(&creator_keypair.pubkey(), &multisig.get_vault_pda(), &mint_info.mint, &mint_info.token_program);
+        let group = vault_ata_groups.last_mut().unwrap();
+        group.push(ix_vault_ata.clone());
+        if !fits_in_transaction(&creator_keypair.pubkey(), group) {
+            group.pop();
+            vault_ata_groups.push(vec![ix_vault_ata]);
+        }
+    }
+
+    let mut steps: Vec<Step> = Vec::with_capacity(vault_ata_groups.len() + 1);
+    for group in vault_ata_groups {
+        let tx_vault_ata = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &group).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx_vault_ata, vec![&creator_keypair]));
+    }
+
+    let threshold = 1;
+    let tx = multisig.transaction_create_multisig(&[], threshold, 0, &create_key).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    steps.push((tx, vec![&creator_keypair, &create_key]));
+
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
     println!("multisig: {}", multisig.get_multisig_pda());
 
+    if !is_dry_run {
+        record_dao(multisig.get_multisig_pda(), create_key.pubkey(), multisig.get_vault_pda(), creator_keypair.pubkey(), threshold).await;
+    }
+
+    let vault_token_accounts: Vec<String> = vault_token_mints
+        .iter()
+        .map(|mint_info| format!(
+            "{{\"mint\":  \"{}\", \"token_program\":  \"{}\", \"token_account\":  \"{}\"}}",
+            mint_info.mint,
+            mint_info.token_program,
+            mint_info.get_token_account(&multisig.get_vault_pda())
+        ))
+        .collect();
+
     Ok(format!(
         "\"multisig_pda\": \"{}\",
         \"vault_pda\":  \"{}\",
-        \"threshold\":  \"{}\"",
-        multisig.get_multisig_pda().to_string(), multisig.get_vault_pda().to_string(), multisig.get_threshold().await.unwrap()
+        \"vault_token_accounts\":  [{}],
+        \"threshold\":  \"{}\",
+        {submission}",
+        multisig.get_multisig_pda().to_string(), multisig.get_vault_pda().to_string(), vault_token_accounts.join(", "), threshold
     ))
 }
 
 pub async fn add_member(
     multisig_pda: String,
     pubkey: String,
-    _permissions: Vec<String>
+    _permissions: Vec<String>,
+    is_dry_run: bool
 ) -> Result<String, String>  {
     dotenv().ok();
 
@@ -157,37 +335,26 @@ pub async fn add_member(
 
     let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
 
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), &[Permission::Initiate, Permission::Vote, Permission::Execute])?;
+
     let new_member_pubkey = Pubkey::from_str(pubkey.as_str()).map_err(|err| format!("\"msg\": \"{err}\""))?;
     let new_member = Member {
         key: new_member_pubkey,
         permissions: Permissions::from_vec(&[Permission::Vote]),
     };
 
-    let mut tx = multisig.transaction_add_member(creator_keypair.pubkey(), new_member).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    // let mut tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_add_member]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let sig = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    println!("sig: {}", sig);
-
-    let ix_prpose = multisig.instruction_proposal_create(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let ix_approve = multisig.instruction_proposal_approve(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let ix_exec = multisig.instruction_config_transaction_execute(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let mut tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_prpose, ix_approve, ix_exec]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let sig = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    println!("sig: {}", sig);
-
-    // let mems = multisig.get_multisig_members().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-
-    // for mem in mems {
-    //     println!("{}", mem.key);
-    // }
+    let ix_add_member = multisig.instructions_add_member(creator_keypair.pubkey(), new_member).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let (steps, transaction_index) = config_transaction_steps(*multisig, &creator_keypair, ix_add_member, vec![]).await?;
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
+    if !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "config").await;
+    }
 
     Ok(
         format!(
-            "\"member\":  \"{}\"",
+            "\"member\":  \"{}\",
+            {submission}",
             new_member_pubkey
         )
     )
@@ -195,7 +362,8 @@ pub async fn add_member(
 
 pub async fn remove_member(
     multisig_pda: String,
-    pubkey: String
+    pubkey: String,
+    is_dry_run: bool
 ) -> Result<String, String>  {
     dotenv().ok();
 
@@ -204,26 +372,22 @@ pub async fn remove_member(
     let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
 
     let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), &[Permission::Initiate, Permission::Vote, Permission::Execute])?;
     let old_member_pubkey = Pubkey::from_str(pubkey.as_str()).map_err(|err| format!("\"msg\": \"{err}\""))?;
 
     let ix_remove_member = multisig.instructions_remove_member(creator_keypair.pubkey(), old_member_pubkey).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let mut tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_remove_member]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let _ = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-
-    let ix_prpose = multisig.instruction_proposal_create(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let ix_approve = multisig.instruction_proposal_approve(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let ix_exec = multisig.instruction_config_transaction_execute(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let mut tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_prpose, ix_approve, ix_exec]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let sig = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    println!("sig: {}", sig);
+    let (steps, transaction_index) = config_transaction_steps(*multisig, &creator_keypair, ix_remove_member, vec![]).await?;
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
+    if !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "config").await;
+    }
 
     Ok(
         format!(
-            "\"member\":  \"{}\"",
+            "\"member\":  \"{}\",
+            {submission}",
             old_member_pubkey
         )
     )
@@ -231,7 +395,8 @@ pub async fn remove_member(
 
 pub async fn change_threshold(
     multisig_pda: String,
-    new_threshold: u16
+    new_threshold: u16,
+    is_dry_run: bool
 ) -> Result<String, String>  {
     dotenv().ok();
 
@@ -241,31 +406,28 @@ pub async fn change_threshold(
 
     let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
 
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), &[Permission::Initiate, Permission::Vote, Permission::Execute])?;
+
     let ix_change_threshold = multisig.instruction_change_threshold(creator_keypair.pubkey(), new_threshold).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let mut tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_change_threshold]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let _ = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-
-    let ix_prpose = multisig.instruction_proposal_create(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let ix_approve = multisig.instruction_proposal_approve(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let ix_exec = multisig.instruction_config_transaction_execute(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let mut tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_prpose, ix_approve, ix_exec]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let sig = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    println!("sig: {}", sig);
+    let (steps, transaction_index) = config_transaction_steps(*multisig, &creator_keypair, ix_change_threshold, vec![]).await?;
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
+    if !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "config").await;
+    }
 
     Ok(
         format!(
-            "\"new_threshold\":  \"{}\"",
+            "\"new_threshold\":  \"{}\",
+            {submission}",
             new_threshold
         )
     )
 }
 
 pub async fn execute_proposal(
-    multisig_pda: String
+    multisig_pda: String,
+    is_dry_run: bool
 ) -> Result<String, String>  {
     dotenv().ok();
 
@@ -275,14 +437,16 @@ pub async fn execute_proposal(
 
     let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
 
-    let mut tx = multisig.transaction_config_transaction_execute(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let _ = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), &[Permission::Execute])?;
+
+    let tx = multisig.transaction_config_transaction_execute(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let submission = submit(multisig.get_rpc_client(), vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
 
     Ok(
         format!(
-            "\"propose\":  \"success\""
+            "\"propose\":  \"success\",
+            {submission}"
         )
     )
 }
@@ -290,7 +454,8 @@ pub async fn execute_proposal(
 pub async fn vote(
     multisig_pda: String,
     voter: String,
-    vote: String
+    vote: String,
+    is_dry_run: bool
 ) -> Result<String, String>  {
     dotenv().ok();
 
@@ -302,89 +467,1506 @@ pub async fn vote(
 
     let multisig: Arc<&dyn InvestorMultisigTrait> = Arc::new(&multisig);
 
-    let mut tx = match vote.as_str() {
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), &[Permission::Vote])?;
+
+    let tx = match vote.as_str() {
         "Cancel" => {
-            multisig.transaction_proposal_cancel(creator_keypair.pubkey()).await.unwrap()
+            multisig.transaction_proposal_cancel(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?
         },
         "Approve" => {
-            multisig.transaction_proposal_approve(creator_keypair.pubkey()).await.unwrap()
+            multisig.transaction_proposal_approve(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?
+        },
+        "Reject" => {
+            multisig.transaction_proposal_reject(creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?
         },
         vote => {
-            return Err(format!("{vote} is not an \"Approve\" or \"Cancel\""));
+            return Err(format!("{vote} is not an \"Approve\", \"Reject\" or \"Cancel\""));
         }
     };
 
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let _ = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let submission = submit(multisig.get_rpc_client(), vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
 
     Ok(
         format!(
             "\"voter\":  \"{}\",
-            \"vote\":  \"{}\"",
+            \"vote\":  \"{}\",
+            {submission}",
             voter,
             vote
         )
     )
 }
 
+/// Puts the milestone reserved for a withdraw back as it was when the withdraw transactions did not land
+async fn restore_milestone_on_failure(
+    store: &MilestoneStore,
+    multisig_pda: &Pubkey,
+    reserved: Option<Milestone>,
+    submission: Result<String, String>
+) -> Result<String, String> {
+    if let (Err(_), Some(previous)) = (&submission, reserved) {
+        store.restore_milestone(multisig_pda, previous).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    }
+
+    submission
+}
+
 pub async fn withdraw(
     multisig_pda: String,
+    proposal_id: String,
     is_execute: bool,
     receiver: String,
-    amount: u64
+    amount: TokenAmount,
+    mint: Option<String>,
+    is_dry_run: bool
 ) -> Result<String, String>  {
     dotenv().ok();
 
-    let creator_keypair = get_ba_keypair().await.unwrap();
-    let multisig_pda = Pubkey::from_str(&multisig_pda).unwrap();
-    let multisig = get_base_multisig(multisig_pda).await.unwrap();
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
 
     let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
 
-    let receiver = Pubkey::from_str(&receiver).unwrap();
+    let receiver = Pubkey::from_str(&receiver).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let required_permissions: &[Permission] = if is_execute { &[Permission::Execute] } else { &[Permission::Initiate, Permission::Vote] };
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), required_permissions)?;
+    let mint = match mint {
+        Some(mint) => Pubkey::from_str(&mint).map_err(|err| format!("\"msg\": \"{err}\""))?,
+        None => get_vault_token_mint()
+    };
+    let mint_info = MintInfo::fetch(multisig.get_rpc_client(), &mint).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let amount = amount.to_raw(mint_info.decimals).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let ui_amount = format_ui_amount(amount, mint_info.decimals);
+    check_vault_token_balance(multisig.get_rpc_client(), &multisig.get_vault_pda(), &mint_info, amount).await.map_err(|err| err.to_response())?;
+    // Transfer fee mints withhold part of every transfer from the receiver
+    let epoch = get_epoch(multisig.get_rpc_client()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let net_amount = mint_info.get_net_amount(epoch, amount).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    // Once a DAO has milestones every withdraw has to release one of them
+    let store = MilestoneStore::open().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let milestones = store.get_milestones(&multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let has_milestones = !milestones.is_empty();
+    let milestone = milestones.into_iter().find(|milestone| milestone.id == proposal_id);
+    if milestone.is_none() && has_milestones {
+        return Err(format!("\"msg\": \"{}\"", MilestoneError::UnknownMilestone(proposal_id)));
+    }
+    // Milestone budgets are in vault token units
+    if milestone.is_some() && mint != get_vault_token_mint() {
+        return Err(format!("\"msg\": \"{}\"", MilestoneError::MintMismatch { id: proposal_id, mint }));
+    }
 
     if is_execute == true {
-        let mut tx = multisig.transaction_vault_transaction_execute(creator_keypair.pubkey(), receiver, amount).await.unwrap();
-        let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.unwrap();
-        let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-        let _ = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.unwrap();
+        let ix_execute = match &milestone {
+            Some(milestone) => {
+                let transaction_index = milestone.check_release(&receiver, amount).map_err(|err| format!("\"msg\": \"{err}\""))?;
+                let ix_transfer = multisig.instruction_vault_token_transfer(&mint_info, receiver, amount).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+                multisig
+                    .instruction_vault_transaction_execute_for_index(creator_keypair.pubkey(), transaction_index, &[ix_transfer], 0)
+                    .map_err(|err| format!("\"msg\": \"{err}\""))?
+            },
+            None => multisig.instruction_vault_transaction_execute(creator_keypair.pubkey(), &mint_info, receiver, amount).await.map_err(|err| format!("\"msg\": \"{err}\""))?
+        };
+        // The transfer inside the vault transaction fails without a token account of the receiver
+        let ix_receiver_ata = create_associated_token_account_idempotent(&creator_keypair.pubkey(), &receiver, &mint_info.mint, &mint_info.token_program);
+        let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_receiver_ata, ix_execute]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+        let reserved = match &milestone {
+            Some(_) if !is_dry_run => Some(
+                store
+                    .reserve_milestone(&multisig_pda, &proposal_id, move |milestone| milestone.release(&receiver, amount))
+                    .await
+                    .map_err(|err| format!("\"msg\": \"{err}\""))?
+            ),
+            _ => None
+        };
+        let submission = submit(multisig.get_rpc_client(), vec![(tx, vec![&creator_keypair])], is_dry_run).await;
+        let submission = restore_milestone_on_failure(&store, &multisig_pda, reserved, submission).await?;
 
         return Ok(
             format!(
                 "\"is_execute\":  \"{}\",
                 \"receiver\":  \"{}\",
-                \"amount\":  \"{}\"",
+                \"amount\":  \"{}\",
+                \"ui_amount\":  \"{}\",
+                \"net_amount\":  \"{}\",
+                \"mint\":  \"{}\",
+                {submission}",
                 is_execute,
                 receiver,
-                amount
+                amount,
+                ui_amount,
+                net_amount,
+                mint
             )
         )
     }
 
-    // let finance = multisig.get_rpc_client().get_balance(&multisig.get_vault_pda()).await.unwrap();;
-    // println!("vault: {}", finance);
-
-    let mut tx = multisig.transaction_transfer_from_vault(creator_keypair.pubkey(), receiver, amount).await.unwrap();
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.unwrap();
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let _ = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.unwrap();
+    if let Some(milestone) = &milestone {
+        milestone.check_propose(&receiver, amount).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    }
 
-    let ix_propose = multisig.instruction_proposal_create(creator_keypair.pubkey()).await.unwrap();
-    let ix_approve = multisig.instruction_proposal_approve(creator_keypair.pubkey()).await.unwrap();
-    let mut tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_propose, ix_approve]).await.unwrap();
-    let recent_blockhash = multisig.get_rpc_client().get_latest_blockhash().await.unwrap();
-    let _ = tx.try_sign(&[&creator_keypair], recent_blockhash);
-    let _ = multisig.get_rpc_client().send_and_confirm_transaction(&tx).await.unwrap();
+    let transaction_index = multisig.get_multisig_transaction_index().await.map_err(|err| format!("\"msg\": \"{err}\""))? + 1;
+    let tx_create = multisig.transaction_transfer_from_vault(creator_keypair.pubkey(), &mint_info, receiver, amount).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let ix_propose = multisig.instruction_proposal_create_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let ix_approve = multisig.instruction_proposal_approve_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let tx_propose = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_propose, ix_approve]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let reserved = match &milestone {
+        Some(_) if !is_dry_run => Some(
+            store
+                .reserve_milestone(&multisig_pda, &proposal_id, move |milestone| milestone.propose(&receiver, amount, transaction_index))
+                .await
+                .map_err(|err| format!("\"msg\": \"{err}\""))?
+        ),
+        _ => None
+    };
+    let submission = submit(multisig.get_rpc_client(), vec![
+        (tx_create, vec![&creator_keypair]),
+        (tx_propose, vec![&creator_keypair])
+    ], is_dry_run).await;
+    let submission = restore_milestone_on_failure(&store, &multisig_pda, reserved, submission).await?;
+
+    if !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "vault").await;
+    }
 
     Ok(
         format!(
             "\"is_execute\":  \"{}\",
             \"receiver\":  \"{}\",
-            \"amount\":  \"{}\"",
+            \"amount\":  \"{}\",
+            \"ui_amount\":  \"{}\",
+            \"net_amount\":  \"{}\",
+            \"mint\":  \"{}\",
+            \"transaction_index\":  {},
+            {submission}",
             is_execute,
             receiver,
+            amount,
+            ui_amount,
+            net_amount,
+            mint,
+            transaction_index
+        )
+    )
+}
+
+/// Registers a milestone of the DAO, `withdraw` with `proposal_id` equal to `milestone_id` releases it
+pub async fn create_milestone(
+    multisig_pda: String,
+    milestone_id: String,
+    amount: u64,
+    recipient: String
+) -> Result<String, String>  {
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let recipient = Pubkey::from_str(&recipient).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let milestone = Milestone::new(milestone_id, amount, recipient).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let response = serde_json::to_string(&milestone).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    MilestoneStore::open()
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?
+        .add_milestone(&multisig_pda, milestone)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    Ok(format!("\"milestone\":  {response}"))
+}
+
+/// Milestones of the DAO with the total budget, what is released and what remains
+pub async fn get_milestones(multisig_pda: String) -> Result<String, String>  {
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let milestones = MilestoneStore::open()
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?
+        .get_milestones(&multisig_pda)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let budget: u64 = milestones.iter().map(|milestone| milestone.amount).sum();
+    let released: u64 = milestones
+        .iter()
+        .filter(|milestone| milestone.status == MilestoneStatus::Released)
+        .map(|milestone| milestone.withdrawn)
+        .sum();
+    let remaining: u64 = milestones
+        .iter()
+        .filter(|milestone| milestone.status != MilestoneStatus::Released)
+        .map(|milestone| milestone.amount)
+        .sum();
+
+    Ok(format!(
+        "\"budget\":  \"{}\",
+        \"released\":  \"{}\",
+        \"remaining\":  \"{}\",
+        \"milestones\":  {}",
+        budget,
+        released,
+        remaining,
+        serde_json::to_string(&milestones).map_err(|err| format!("\"msg\": \"{err}\""))?
+    ))
+}
+
+/// Config change accepted by `batch_config`, `type` selects the variant
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchConfigAction {
+    AddMember { pubkey: String, permissions: Vec<String> },
+    RemoveMember { pubkey: String },
+    ChangeThreshold { new_threshold: u16 },
+    SetTimeLock { new_time_lock: u32 },
+    SetRentCollector { new_rent_collector: Option<String> },
+}
+
+fn parse_permissions(permissions: &[String]) -> Result<Permissions, String> {
+    let permissions = permissions
+        .iter()
+        .map(|permission| match permission.as_str() {
+            "Initiate" => Ok(Permission::Initiate),
+            "Vote" => Ok(Permission::Vote),
+            "Execute" => Ok(Permission::Execute),
+            permission => Err(format!("\"msg\": \"{permission} is not an Initiate, Vote or Execute permission\""))
+        })
+        .collect::<Result<Vec<Permission>, String>>()?;
+
+    Ok(Permissions::from_vec(&permissions))
+}
+
+fn parse_config_action(action: BatchConfigAction) -> Result<ConfigAction, String> {
+    let action = match action {
+        BatchConfigAction::AddMember { pubkey, permissions } => ConfigAction::AddMember {
+            new_member: Member {
+                key: Pubkey::from_str(&pubkey).map_err(|err| format!("\"msg\": \"{err}\""))?,
+                permissions: parse_permissions(&permissions)?,
+            }
+        },
+        BatchConfigAction::RemoveMember { pubkey } => ConfigAction::RemoveMember {
+            old_member: Pubkey::from_str(&pubkey).map_err(|err| format!("\"msg\": \"{err}\""))?
+        },
+        BatchConfigAction::ChangeThreshold { new_threshold } => ConfigAction::ChangeThreshold { new_threshold },
+        BatchConfigAction::SetTimeLock { new_time_lock } => ConfigAction::SetTimeLock { new_time_lock },
+        BatchConfigAction::SetRentCollector { new_rent_collector } => ConfigAction::SetRentCollector {
+            new_rent_collector: new_rent_collector
+                .map(|rent_collector| Pubkey::from_str(&rent_collector))
+                .transpose()
+                .map_err(|err| format!("\"msg\": \"{err}\""))?
+        },
+    };
+
+    Ok(action)
+}
+
+pub async fn batch_config(
+    multisig_pda: String,
+    actions: Vec<BatchConfigAction>,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), &[Permission::Initiate, Permission::Vote, Permission::Execute])?;
+
+    let actions = actions
+        .into_iter()
+        .map(parse_config_action)
+        .collect::<Result<Vec<ConfigAction>, String>>()?;
+    let actions_count = actions.len();
+
+    // Membership and threshold are validated while building the instruction, nothing is sent on failure
+    let ix_batch_config = multisig.instruction_batch_config(creator_keypair.pubkey(), actions).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let (steps, transaction_index) = config_transaction_steps(*multisig, &creator_keypair, ix_batch_config, vec![]).await?;
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
+    if !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "config").await;
+    }
+
+    if is_dry_run {
+        return Ok(
+            format!(
+                "\"actions\":  \"{}\",
+                {submission}",
+                actions_count
+            )
+        )
+    }
+
+    Ok(
+        format!(
+            "\"actions\":  \"{}\",
+            \"threshold\":  \"{}\",
+            \"members\":  \"{}\",
+            {submission}",
+            actions_count,
+            multisig.get_threshold().await.map_err(|err| format!("\"msg\": \"{err}\""))?,
+            multisig.get_multisig_members().await.map_err(|err| format!("\"msg\": \"{err}\""))?.len()
+        )
+    )
+}
+
+fn parse_period(period: &str) -> Result<Period, String> {
+    match period {
+        "OneTime" => Ok(Period::OneTime),
+        "Day" => Ok(Period::Day),
+        "Week" => Ok(Period::Week),
+        "Month" => Ok(Period::Month),
+        period => Err(format!("\"msg\": \"{period} is not a OneTime, Day, Week or Month period\""))
+    }
+}
+
+pub async fn add_spending_limit(
+    multisig_pda: String,
+    mint: String,
+    amount: u64,
+    period: String,
+    members: Vec<String>,
+    destinations: Vec<String>,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), &[Permission::Initiate, Permission::Vote, Permission::Execute])?;
+
+    let mint = Pubkey::from_str(&mint).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let period = parse_period(&period)?;
+    let members = members
+        .iter()
+        .map(|member| Pubkey::from_str(member).map_err(|err| format!("\"msg\": \"{err}\"")))
+        .collect::<Result<Vec<Pubkey>, String>>()?;
+    let destinations = destinations
+        .iter()
+        .map(|destination| Pubkey::from_str(destination).map_err(|err| format!("\"msg\": \"{err}\"")))
+        .collect::<Result<Vec<Pubkey>, String>>()?;
+
+    let spending_limit_create_key = Keypair::new().pubkey();
+    let spending_limit = get_multisig_spending_limit_pda(&multisig_pda, &spending_limit_create_key);
+
+    let args = AddSpendingLimitArgs { create_key: spending_limit_create_key, mint, amount, period, members, destinations };
+    let ix_add_spending_limit = multisig.instruction_add_spending_limit(creator_keypair.pubkey(), args).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let (steps, transaction_index) = config_transaction_steps(*multisig, &creator_keypair, ix_add_spending_limit, vec![spending_limit]).await?;
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
+    if !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "config").await;
+    }
+
+    Ok(
+        format!(
+            "\"spending_limit\":  \"{}\",
+            \"mint\":  \"{}\",
+            \"amount\":  \"{}\",
+            \"period\":  \"{:?}\",
+            {submission}",
+            spending_limit,
+            mint,
+            amount,
+            period
+        )
+    )
+}
+
+pub async fn remove_spending_limit(
+    multisig_pda: String,
+    spending_limit: String,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), &[Permission::Initiate, Permission::Vote, Permission::Execute])?;
+    let spending_limit = Pubkey::from_str(&spending_limit).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let ix_remove_spending_limit = multisig.instruction_remove_spending_limit(creator_keypair.pubkey(), spending_limit).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let (steps, transaction_index) = config_transaction_steps(*multisig, &creator_keypair, ix_remove_spending_limit, vec![spending_limit]).await?;
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
+    if !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "config").await;
+    }
+
+    Ok(
+        format!(
+            "\"spending_limit\":  \"{}\",
+            {submission}",
+            spending_limit
+        )
+    )
+}
+
+pub async fn use_spending_limit(
+    multisig_pda: String,
+    spending_limit: String,
+    receiver: String,
+    amount: u64,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+    let spending_limit = Pubkey::from_str(&spending_limit).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let receiver = Pubkey::from_str(&receiver).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let tx = multisig.transaction_spending_limit_use(creator_keypair.pubkey(), spending_limit, receiver, amount).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let submission = submit(multisig.get_rpc_client(), vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
+
+    Ok(
+        format!(
+            "\"spending_limit\":  \"{}\",
+            \"receiver\":  \"{}\",
+            \"amount\":  \"{}\",
+            {submission}",
+            spending_limit,
+            receiver,
             amount
         )
     )
-}
\ No newline at end of file
+}
+
+pub async fn get_spending_limits(
+    multisig_pda: String
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let spending_limits = multisig.get_spending_limits().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let spending_limits = spending_limits
+        .iter()
+        .map(|(spending_limit, state)| {
+            let members = state.members.iter().map(|member| format!("\"{member}\"")).collect::<Vec<String>>().join(", ");
+            let destinations = state.destinations.iter().map(|destination| format!("\"{destination}\"")).collect::<Vec<String>>().join(", ");
+
+            format!(
+                "{{
+                \"spending_limit\":  \"{}\",
+                \"mint\":  \"{}\",
+                \"amount\":  \"{}\",
+                \"remaining_amount\":  \"{}\",
+                \"period\":  \"{:?}\",
+                \"last_reset\":  {},
+                \"members\":  [{}],
+                \"destinations\":  [{}]
+                }}",
+                spending_limit,
+                state.mint,
+                state.amount,
+                state.remaining_amount,
+                state.period,
+                state.last_reset,
+                members,
+                destinations
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(
+        format!(
+            "\"spending_limits\":  [{}]",
+            spending_limits
+        )
+    )
+}
+
+/// Lamports and token accounts of every mint held by the DAO vault, for both token programs
+pub async fn get_dao_balances(
+    multisig_pda: String
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let balances = multisig.get_vault_balances().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    Ok(
+        format!(
+            "\"vault_pda\":  \"{}\",
+            {}",
+            multisig.get_vault_pda(),
+            balances.to_json()
+        )
+    )
+}
+
+/// Platform history of the DAO rebuilt from the memos of its squads transactions on chain, oldest first.
+/// `limit` bounds how many of the latest transactions of the multisig are read
+pub async fn get_dao_history(
+    multisig_pda: String,
+    limit: Option<usize>
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let rpc_client = get_rpc_client().map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let history = get_audit_entries(&rpc_client, &multisig_pda, limit).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let history: Vec<serde_json::Value> = history.iter().map(|entry| entry.to_json()).collect();
+
+    Ok(
+        format!(
+            "\"history\":  {}",
+            serde_json::Value::Array(history)
+        )
+    )
+}
+
+/// Builds an approve or reject of the current proposal for `voter` to sign in their wallet.
+/// The transaction uses the durable nonce of the voter's nonce account instead of a blockhash,
+/// so it stays valid for as long as the wallet takes. The nonce account is created on first use
+pub async fn prepare_vote(
+    multisig_pda: String,
+    voter: String,
+    vote: String,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let voter = Pubkey::from_str(voter.as_str()).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn InvestorMultisigTrait> = Arc::new(&multisig);
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    check_member_permissions(&multisig_state, &voter, &[Permission::Vote])?;
+
+    let ix_vote = match vote.as_str() {
+        "Approve" => {
+            multisig.instruction_proposal_approve(voter).await.map_err(|err| format!("\"msg\": \"{err}\""))?
+        },
+        "Reject" => {
+            multisig.instruction_proposal_reject(voter).await.map_err(|err| format!("\"msg\": \"{err}\""))?
+        },
+        vote => {
+            return Err(format!("\"msg\": \"{vote} is not an Approve or Reject\""));
+        }
+    };
+
+    let rpc_client = multisig.get_rpc_client();
+    let nonce_account = get_nonce_account_address(&creator_keypair.pubkey(), &multisig_pda, &voter);
+
+    let nonce = match get_nonce(rpc_client, &nonce_account, &creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))? {
+        Some(nonce) => nonce,
+        None => {
+            let ixs_create = instructions_create_nonce_account(rpc_client, &creator_keypair.pubkey(), &multisig_pda, &voter).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            let tx_create = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &ixs_create).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            let submission = submit(rpc_client, vec![(tx_create, vec![&creator_keypair])], is_dry_run).await?;
+
+            // Without the account there is no nonce to build the vote on
+            if is_dry_run {
+                return Ok(
+                    format!(
+                        "\"voter\":  \"{}\",
+                        \"vote\":  \"{}\",
+                        \"nonce_account\":  \"{}\",
+                        {submission}",
+                        voter,
+                        vote,
+                        nonce_account
+                    )
+                )
+            }
+
+            get_nonce(rpc_client, &nonce_account, &creator_keypair.pubkey())
+                .await
+                .map_err(|err| format!("\"msg\": \"{err}\""))?
+                .ok_or_else(|| format!("\"msg\": \"Nonce account {nonce_account} was not created\""))?
+        }
+    };
+
+    let mut tx = get_nonce_transaction(&[ix_vote], &voter, &nonce_account, &creator_keypair.pubkey(), nonce);
+    tx.try_partial_sign(&[&creator_keypair], nonce).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let serialized = bincode::serialize(&tx).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    Ok(
+        format!(
+            "\"voter\":  \"{}\",
+            \"vote\":  \"{}\",
+            \"nonce_account\":  \"{}\",
+            \"nonce\":  \"{}\",
+            \"transaction\":  \"{}\"",
+            voter,
+            vote,
+            nonce_account,
+            nonce,
+            BASE64.encode(serialized)
+        )
+    )
+}
+
+/// Sends a vote prepared by `prepare_vote` once the voter signed it
+pub async fn submit_signed_vote(
+    multisig_pda: String,
+    voter: String,
+    transaction: String,
+    commitment: Option<String>,
+    timeout_ms: Option<u64>
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let voter = Pubkey::from_str(voter.as_str()).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let rpc_client = get_rpc_client()?;
+
+    let serialized = BASE64.decode(transaction).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let tx: Transaction = bincode::deserialize(&serialized).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let nonce_account = get_nonce_account_address(&creator_keypair.pubkey(), &multisig_pda, &voter);
+    check_nonce_transaction(&tx, &nonce_account).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    tx.verify().map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let config = ConfirmationConfig::from_env()
+        .and_then(|config| config.with_request(commitment, timeout_ms))
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    // The nonce replaces the blockhash, the transaction can not expire and is sent as it is
+    let sig = rpc_client.send_transaction(&tx).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    notify_submitted(Submission::Broadcast(sig));
+    let confirmation = get_confirmation_tracker()
+        .wait_for_confirmation(&rpc_client, &sig, &config)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+    record_signature(&sig, confirmation.name()).await;
+
+    if let Confirmation::Failed { err, .. } = &confirmation {
+        let msg = format!("Transaction {sig} failed: {err}");
+        return Err(format!(
+            "\"msg\":  {},
+            \"status\":  \"{}\",
+            \"signatures\":  [\"{}\"]",
+            serde_json::to_string(&msg).unwrap_or_default(),
+            confirmation.name(),
+            sig
+        ));
+    }
+
+    // A timed out vote may still land, its nonce keeps it valid until the nonce is rotated
+    Ok(
+        format!(
+            "\"voter\":  \"{}\",
+            \"status\":  \"{}\",
+            \"signatures\":  [\"{}\"]",
+            voter,
+            confirmation.name(),
+            sig
+        )
+    )
+}
+
+/// Advances the member's nonce, every vote prepared before and not yet sent becomes invalid
+pub async fn rotate_nonce_account(
+    multisig_pda: String,
+    member: String,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let member = Pubkey::from_str(member.as_str()).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let nonce_account = get_nonce_account_address(&creator_keypair.pubkey(), &multisig_pda, &member);
+    get_nonce(multisig.get_rpc_client(), &nonce_account, &creator_keypair.pubkey())
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?
+        .ok_or_else(|| format!("\"msg\": \"Nonce account {nonce_account} does not exist\""))?;
+
+    let ix_rotate = instruction_rotate_nonce_account(&nonce_account, &creator_keypair.pubkey());
+    let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_rotate]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let submission = submit(multisig.get_rpc_client(), vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
+
+    Ok(
+        format!(
+            "\"member\":  \"{}\",
+            \"nonce_account\":  \"{}\",
+            {submission}",
+            member,
+            nonce_account
+        )
+    )
+}
+
+/// Closes the member's nonce account and returns its rent to the operator
+pub async fn close_nonce_account(
+    multisig_pda: String,
+    member: String,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let member = Pubkey::from_str(member.as_str()).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let nonce_account = get_nonce_account_address(&creator_keypair.pubkey(), &multisig_pda, &member);
+    get_nonce(multisig.get_rpc_client(), &nonce_account, &creator_keypair.pubkey())
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?
+        .ok_or_else(|| format!("\"msg\": \"Nonce account {nonce_account} does not exist\""))?;
+
+    let ix_close = instruction_close_nonce_account(multisig.get_rpc_client(), &nonce_account, &creator_keypair.pubkey()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_close]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let submission = submit(multisig.get_rpc_client(), vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
+
+    Ok(
+        format!(
+            "\"member\":  \"{}\",
+            \"nonce_account\":  \"{}\",
+            {submission}",
+            member,
+            nonce_account
+        )
+    )
+}
+
+/// Closes a transaction buffer left behind by a vault transaction that was never created from it
+pub async fn close_transaction_buffer(
+    multisig_pda: String,
+    buffer_index: u8,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+
+    let transaction_buffer = get_transaction_buffer_pda(&multisig_pda, &creator_keypair.pubkey(), buffer_index);
+    let ix_close = multisig.instruction_transaction_buffer_close(creator_keypair.pubkey(), buffer_index);
+    let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_close]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let submission = submit(multisig.get_rpc_client(), vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
+
+    Ok(
+        format!(
+            "\"transaction_buffer\":  \"{}\",
+            {submission}",
+            transaction_buffer
+        )
+    )
+}
+
+/// Investor of a `refund`, weights are relative to each other
+#[derive(Deserialize, Debug)]
+pub struct RefundInvestor {
+    pub pubkey: String,
+    pub weight: u64,
+}
+
+/// Returns everything in the vault token account to investors pro rata. Weights come from `investors`
+/// or, when `snapshot_mint` is set, from the balances of the holders of that mint. Creates one vault
+/// transaction per `REFUND_INVESTORS_PER_TRANSACTION` investors, each also creating the investor token
+/// accounts that are missing, and proposes and approves them. With `is_execute` they are executed as well
+pub async fn refund(
+    multisig_pda: String,
+    investors: Vec<RefundInvestor>,
+    snapshot_mint: Option<String>,
+    is_execute: bool,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+    let rpc_client = multisig.get_rpc_client();
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let required_permissions: &[Permission] = if is_execute { &[Permission::Initiate, Permission::Vote, Permission::Execute] } else { &[Permission::Initiate, Permission::Vote] };
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), required_permissions)?;
+
+    let mint_info = MintInfo::fetch(rpc_client, &get_vault_token_mint()).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let vault_pda = multisig.get_vault_pda();
+    let vault_token_account = mint_info.get_token_account(&vault_pda);
+    let vault_balance = rpc_client
+        .get_account(&vault_token_account)
+        .await
+        .ok()
+        .and_then(|account| unpack_token_account(&vault_token_account, &account).ok())
+        .map(|account| account.amount)
+        .ok_or_else(|| PreflightError::FailedToFetchTokenAccount(vault_token_account).to_response())?;
+    if vault_balance == 0 {
+        return Err(format!("\"msg\": \"{}\"", RefundError::EmptyVault(vault_token_account)));
+    }
+
+    let weights = match snapshot_mint {
+        Some(snapshot_mint) => {
+            let snapshot_mint = Pubkey::from_str(&snapshot_mint).map_err(|err| format!("\"msg\": \"{err}\""))?;
+            let snapshot_mint_info = MintInfo::fetch(rpc_client, &snapshot_mint).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            get_snapshot_weights(rpc_client, &snapshot_mint_info, &[vault_pda]).await.map_err(|err| format!("\"msg\": \"{err}\""))?
+        },
+        None => investors
+            .iter()
+            .map(|investor| Ok((Pubkey::from_str(&investor.pubkey).map_err(|err| format!("\"msg\": \"{err}\""))?, investor.weight)))
+            .collect::<Result<Vec<(Pubkey, u64)>, String>>()?
+    };
+
+    let shares = split_pro_rata(vault_balance, &weights).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let paid_shares: Vec<&RefundShare> = shares.iter().filter(|share| share.amount > 0).collect();
+
+    let investor_token_accounts: Vec<Pubkey> = paid_shares
+        .iter()
+        .map(|share| mint_info.get_token_account(&share.investor))
+        .collect();
+    let mut missing_token_accounts = Vec::with_capacity(investor_token_accounts.len());
+    for token_accounts in investor_token_accounts.chunks(100) {
+        let accounts = rpc_client.get_multiple_accounts(token_accounts).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        missing_token_accounts.extend(accounts.iter().map(Option::is_none));
+    }
+
+    // The vault pays for the token accounts it creates
+    let missing_count = missing_token_accounts.iter().filter(|missing| **missing).count() as u64;
+    if missing_count > 0 {
+        let rent = rpc_client.get_minimum_balance_for_rent_exemption(mint_info.token_account_len).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let vault_lamports = rpc_client.get_balance(&vault_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        if vault_lamports < rent * missing_count {
+            return Err(format!("\"msg\": \"{}\"", RefundError::InsufficientVaultLamports { balance: vault_lamports, required: rent * missing_count }));
+        }
+    }
+
+    let first_transaction_index = multisig.get_multisig_transaction_index().await.map_err(|err| format!("\"msg\": \"{err}\""))? + 1;
+    let epoch = get_epoch(rpc_client).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let transactions_count = paid_shares.len().div_ceil(REFUND_INVESTORS_PER_TRANSACTION);
+
+    let mut steps: Vec<Step> = Vec::new();
+    let mut refunds = Vec::with_capacity(shares.len());
+    for (chunk_index, chunk) in paid_shares.chunks(REFUND_INVESTORS_PER_TRANSACTION).enumerate() {
+        let transaction_index = first_transaction_index + chunk_index as u64;
+
+        let mut vault_instructions = Vec::with_capacity(chunk.len() * 2);
+        for (offset, share) in chunk.iter().enumerate() {
+            let position = chunk_index * REFUND_INVESTORS_PER_TRANSACTION + offset;
+            let investor_token_account = investor_token_accounts[position];
+            let is_missing = missing_token_accounts[position];
+
+            if is_missing {
+                vault_instructions.push(create_associated_token_account_idempotent(&vault_pda, &share.investor, &mint_info.mint, &mint_info.token_program));
+            }
+            vault_instructions.push(
+                mint_info
+                    .instruction_transfer_checked(rpc_client, &vault_token_account, &investor_token_account, &vault_pda, share.amount)
+                    .await
+                    .map_err(|err| format!("\"msg\": \"{err}\""))?
+            );
+            let net_amount = mint_info.get_net_amount(epoch, share.amount).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+            refunds.push(format!(
+                "{{\"investor\":  \"{}\", \"weight\":  \"{}\", \"amount\":  \"{}\", \"net_amount\":  \"{}\", \"token_account\":  \"{}\", \"create_token_account\":  {}, \"transaction_index\":  {}}}",
+                share.investor, share.weight, share.amount, net_amount, investor_token_account, is_missing, transaction_index
+            ));
+        }
+
+        let memo = Some(format!("Refund {} of {transactions_count} from {vault_pda}", chunk_index + 1));
+        let groups = multisig
+            .instructions_vault_transaction_create(creator_keypair.pubkey(), transaction_index, &vault_instructions, 0, memo)
+            .await
+            .map_err(|err| format!("\"msg\": \"{err}\""))?;
+        for group in groups {
+            let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &group).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            steps.push((tx, vec![&creator_keypair]));
+        }
+
+        let ix_propose = multisig.instruction_proposal_create_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let ix_approve = multisig.instruction_proposal_approve_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let tx_propose = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_propose, ix_approve]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx_propose, vec![&creator_keypair]));
+
+        if is_execute {
+            let ix_execute = multisig
+                .instruction_vault_transaction_execute_for_index(creator_keypair.pubkey(), transaction_index, &vault_instructions, 0)
+                .map_err(|err| format!("\"msg\": \"{err}\""))?;
+            let tx_execute = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_execute]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            steps.push((tx_execute, vec![&creator_keypair]));
+        }
+    }
+
+    // Investors whose share rounds down to zero get nothing sent but are still reported
+    for share in shares.iter().filter(|share| share.amount == 0) {
+        refunds.push(format!(
+            "{{\"investor\":  \"{}\", \"weight\":  \"{}\", \"amount\":  \"0\", \"token_account\":  null, \"create_token_account\":  false, \"transaction_index\":  null}}",
+            share.investor, share.weight
+        ));
+    }
+
+    let submission = submit(rpc_client, steps, is_dry_run).await?;
+    if !is_dry_run {
+        for transaction_index in first_transaction_index..first_transaction_index + transactions_count as u64 {
+            record_proposal(&multisig_pda, transaction_index, "vault").await;
+        }
+    }
+
+    Ok(
+        format!(
+            "\"is_execute\":  \"{}\",
+            \"mint\":  \"{}\",
+            \"vault_balance\":  \"{}\",
+            \"refunds\":  [{}],
+            {submission}",
+            is_execute,
+            mint_info.mint,
+            vault_balance,
+            refunds.join(", ")
+        )
+    )
+}
+
+/// Closes every executed, rejected, cancelled or stale transaction of the DAO together with its proposal
+/// and returns their rent to the multisig rent collector
+pub async fn reclaim_rent(
+    multisig_pda: String,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let rent_collector = multisig_state
+        .rent_collector
+        .ok_or_else(|| format!("\"msg\": \"{}\"", RentError::RentCollectorNotSet))?;
+
+    let closable = get_closable_transactions(multisig.get_rpc_client(), &multisig_pda, &multisig_state)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let reclaimed_lamports: u64 = closable.iter().map(|transaction| transaction.lamports).sum();
+    let transactions: Vec<String> = closable
+        .iter()
+        .map(|transaction| format!(
+            "{{\"transaction_index\":  {}, \"kind\":  \"{}\", \"lamports\":  {}}}",
+            transaction.transaction_index, transaction.kind.name(), transaction.lamports
+        ))
+        .collect();
+
+    if closable.is_empty() {
+        return Ok(
+            format!(
+                "\"rent_collector\":  \"{}\",
+                \"transactions\":  [],
+                \"reclaimed_lamports\":  0",
+                rent_collector
+            )
+        )
+    }
+
+    let mut steps: Vec<Step> = Vec::new();
+    for chunk in closable.chunks(CLOSES_PER_TRANSACTION) {
+        let instructions: Vec<Instruction> = chunk
+            .iter()
+            .map(|transaction| match transaction.kind {
+                TransactionKind::Config => multisig.instruction_config_transaction_accounts_close(rent_collector, transaction.transaction_index),
+                TransactionKind::Vault => multisig.instruction_vault_transaction_accounts_close(rent_collector, transaction.transaction_index),
+            })
+            .collect();
+        let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &instructions).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx, vec![&creator_keypair]));
+    }
+
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
+
+    Ok(
+        format!(
+            "\"rent_collector\":  \"{}\",
+            \"transactions\":  [{}],
+            \"reclaimed_lamports\":  {},
+            {submission}",
+            rent_collector,
+            transactions.join(", "),
+            reclaimed_lamports
+        )
+    )
+}
+
+/// DAOs created by the service with the key their multisig was created with
+pub async fn get_daos() -> Result<String, String>  {
+    let store = get_store().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let daos = store.get_daos().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let daos: Vec<String> = daos
+        .iter()
+        .map(|dao| format!(
+            "{{\"multisig_pda\":  \"{}\", \"create_key\":  \"{}\", \"vault_pda\":  \"{}\", \"creator\":  \"{}\", \"threshold\":  {}, \"request_id\":  {}, \"created_at\":  {}}}",
+            dao.multisig_pda,
+            dao.create_key,
+            dao.vault_pda,
+            dao.creator,
+            dao.threshold,
+            dao.request_id.map(|id| id.to_string()).unwrap_or_else(|| "null".into()),
+            dao.created_at
+        ))
+        .collect();
+
+    Ok(format!("\"daos\":  [{}]", daos.join(", ")))
+}
+
+/// Request that created the squads transaction at `transaction_index` and the signatures it sent
+pub async fn get_proposal(multisig_pda: String, transaction_index: u64) -> Result<String, String>  {
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let store = get_store().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let proposal = store
+        .get_proposal(&multisig_pda, transaction_index)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?
+        .ok_or_else(|| format!("\"msg\": \"Transaction {transaction_index} was not created by this service\""))?;
+
+    let request = match proposal.request_id {
+        Some(request_id) => store.get_request(request_id).await.map_err(|err| format!("\"msg\": \"{err}\""))?,
+        None => None
+    };
+    let request = match request {
+        Some(request) => {
+            let signatures = store.get_request_signatures(request.id).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+            format!(
+                "{{\"id\":  {}, \"command\":  \"{}\", \"correlation_id\":  {}, \"payload\":  {}, \"status\":  \"{}\", \"created_at\":  {}, \"signatures\":  [{}]}}",
+                request.id,
+                request.command,
+                serde_json::to_string(&request.correlation_id).unwrap_or_default(),
+                serde_json::to_string(&request.payload).unwrap_or_default(),
+                request.status,
+                request.created_at,
+                signatures.iter().map(|signature| format!("\"{signature}\"")).collect::<Vec<String>>().join(", ")
+            )
+        },
+        None => "null".to_string()
+    };
+
+    Ok(format!(
+        "\"transaction_index\":  {},
+        \"kind\":  \"{}\",
+        \"created_at\":  {},
+        \"request\":  {}",
+        proposal.transaction_index,
+        proposal.kind,
+        proposal.created_at,
+        request
+    ))
+}
+
+async fn get_venture_launch(vault_account: String, data_account: String) -> Result<VentureLaunch, String> {
+    let vault_account = Pubkey::from_str(&vault_account).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let data_account = Pubkey::from_str(&data_account).map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    VentureLaunch::from_vault(get_rpc_client()?, get_program_id(), vault_account, data_account)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))
+}
+
+/// Creates an escrow vault of the VentureLaunch program for `mint`, by default the vault token mint of the DAOs
+pub async fn create_vault(mint: Option<String>, is_dry_run: bool) -> Result<String, String>  {
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let mint = match mint {
+        Some(mint) => Pubkey::from_str(&mint).map_err(|err| format!("\"msg\": \"{err}\""))?,
+        None => get_vault_token_mint()
+    };
+
+    let vault_account = Keypair::new();
+    let data_account = Keypair::new();
+    let mut vl = VentureLaunch::new(get_rpc_client()?, get_program_id(), vault_account.pubkey(), data_account.pubkey(), mint);
+    let tx = vl
+        .transaction_create_vault(&creator_keypair.pubkey(), &vault_account, &data_account)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let submission = submit(&vl.rpc_client, vec![(tx, vec![&creator_keypair, &vault_account, &data_account])], is_dry_run).await?;
+
+    Ok(format!(
+        "\"vault_account\":  \"{}\",
+        \"data_account\":  \"{}\",
+        \"mint\":  \"{}\",
+        {submission}",
+        vl.vault_account, vl.data_account, vl.mint
+    ))
+}
+
+/// Deposits `amount` from the operator's token account of the vault mint
+pub async fn deposit_to_vault(vault_account: String, data_account: String, amount: u64, is_dry_run: bool) -> Result<String, String>  {
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let vl = get_venture_launch(vault_account, data_account).await?;
+
+    let deposit_account = get_associated_token_address(&creator_keypair.pubkey(), &vl.mint);
+    let tx = vl.transaction_deposit(&creator_keypair.pubkey(), &deposit_account, amount);
+
+    let submission = submit(&vl.rpc_client, vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
+
+    Ok(format!(
+        "\"vault_account\":  \"{}\",
+        \"amount\":  \"{}\",
+        {submission}",
+        vl.vault_account, amount
+    ))
+}
+
+/// Withdraws `amount` from the vault to the token account of `receiver` for the vault mint
+pub async fn withdraw_from_vault(vault_account: String, data_account: String, receiver: String, amount: u64, is_dry_run: bool) -> Result<String, String>  {
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let receiver = Pubkey::from_str(&receiver).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let vl = get_venture_launch(vault_account, data_account).await?;
+
+    let tx = vl.transaction_withdraw(&creator_keypair.pubkey(), &receiver, amount);
+
+    let submission = submit(&vl.rpc_client, vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
+
+    Ok(format!(
+        "\"vault_account\":  \"{}\",
+        \"receiver\":  \"{}\",
+        \"amount\":  \"{}\",
+        {submission}",
+        vl.vault_account, receiver, amount
+    ))
+}
+
+/// Amount the data account of the vault tracks as deposited, with the authority allowed to withdraw it
+pub async fn get_vault_balance(vault_account: String, data_account: String) -> Result<String, String>  {
+    let vl = get_venture_launch(vault_account, data_account).await?;
+    let vault = vl.get_vault().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    Ok(format!(
+        "\"vault_account\":  \"{}\",
+        \"mint\":  \"{}\",
+        \"authority\":  \"{}\",
+        \"balance\":  \"{}\"",
+        vault.vault, vault.mint, vault.authority, vault.amount
+    ))
+}
+
+/// Proposes a vault transaction creating a VentureLaunch escrow for `mint` with the DAO vault as initializer,
+/// so withdrawing from it needs the approval of the DAO. With `transaction_index` the approved proposal is executed
+pub async fn create_escrow(
+    multisig_pda: String,
+    mint: Option<String>,
+    transaction_index: Option<u64>,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+    let rpc_client = multisig.get_rpc_client();
+
+    let mint = match mint {
+        Some(mint) => Pubkey::from_str(&mint).map_err(|err| format!("\"msg\": \"{err}\""))?,
+        None => get_vault_token_mint()
+    };
+    let mint_info = MintInfo::fetch(rpc_client, &mint).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let program_id = get_program_id();
+    let is_execute = transaction_index.is_some();
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let required_permissions: &[Permission] = if is_execute { &[Permission::Execute] } else { &[Permission::Initiate, Permission::Vote] };
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), required_permissions)?;
+
+    let transaction_index = match transaction_index {
+        Some(transaction_index) => transaction_index,
+        None => multisig_state.transaction_index + 1
+    };
+    let vault_instructions = multisig
+        .instructions_venture_launch_create_vault(program_id, &mint_info, transaction_index)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    // The vault pays the rent of the escrow accounts when the transaction is executed
+    let vault_pda = multisig.get_vault_pda();
+    let vault_rent = rpc_client.get_minimum_balance_for_rent_exemption(mint_info.token_account_len).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let data_rent = rpc_client.get_minimum_balance_for_rent_exemption(CryptoTracker::LEN).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let vault_lamports = rpc_client.get_balance(&vault_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    if vault_lamports < vault_rent + data_rent {
+        return Err(format!("\"msg\": \"{}\"", EscrowError::InsufficientVaultLamports { balance: vault_lamports, required: vault_rent + data_rent }));
+    }
+
+    let mut steps: Vec<Step> = Vec::new();
+    if is_execute {
+        let ix_execute = multisig
+            .instruction_vault_transaction_execute_for_index(creator_keypair.pubkey(), transaction_index, &vault_instructions, VENTURE_LAUNCH_CREATE_VAULT_EPHEMERAL_SIGNERS)
+            .map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let tx_execute = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_execute]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx_execute, vec![&creator_keypair]));
+    } else {
+        let memo = Some(format!("Create escrow for {mint}"));
+        let groups = multisig
+            .instructions_vault_transaction_create(creator_keypair.pubkey(), transaction_index, &vault_instructions, VENTURE_LAUNCH_CREATE_VAULT_EPHEMERAL_SIGNERS, memo)
+            .await
+            .map_err(|err| format!("\"msg\": \"{err}\""))?;
+        for group in groups {
+            let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &group).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            steps.push((tx, vec![&creator_keypair]));
+        }
+
+        let ix_propose = multisig.instruction_proposal_create_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let ix_approve = multisig.instruction_proposal_approve_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let tx_propose = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_propose, ix_approve]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx_propose, vec![&creator_keypair]));
+    }
+
+    let submission = submit(rpc_client, steps, is_dry_run).await?;
+    if !is_execute && !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "vault").await;
+    }
+
+    Ok(format!(
+        "\"is_execute\":  \"{}\",
+        \"vault_account\":  \"{}\",
+        \"data_account\":  \"{}\",
+        \"authority\":  \"{}\",
+        \"mint\":  \"{}\",
+        \"transaction_index\":  {},
+        {submission}",
+        is_execute,
+        multisig.get_ephemeral_signer(transaction_index, 0),
+        multisig.get_ephemeral_signer(transaction_index, 1),
+        vault_pda,
+        mint,
+        transaction_index
+    ))
+}
+
+/// Proposes a vault transaction withdrawing `amount` from an escrow created by `create_escrow` to the token account
+/// of `receiver` for the escrow mint. With `transaction_index` the approved proposal is executed
+pub async fn withdraw_from_escrow(
+    multisig_pda: String,
+    vault_account: String,
+    data_account: String,
+    receiver: String,
+    amount: u64,
+    transaction_index: Option<u64>,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+
+    let receiver = Pubkey::from_str(&receiver).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let vl = get_venture_launch(vault_account, data_account).await?;
+    let is_execute = transaction_index.is_some();
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let required_permissions: &[Permission] = if is_execute { &[Permission::Execute] } else { &[Permission::Initiate, Permission::Vote] };
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), required_permissions)?;
+
+    let vault = vl.get_vault().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    if vault.authority != multisig.get_vault_pda() {
+        return Err(format!("\"msg\": \"{}\"", EscrowError::NotVaultAuthority { vault_account: vault.vault, authority: vault.authority }));
+    }
+    if vault.amount < amount {
+        return Err(format!("\"msg\": \"{}\"", EscrowError::InsufficientEscrowBalance { vault_account: vault.vault, balance: vault.amount, required: amount }));
+    }
+
+    let mint_info = MintInfo::fetch(multisig.get_rpc_client(), &vl.mint).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let receive_account = mint_info.get_token_account(&receiver);
+    let vault_instructions = vec![
+        multisig.instruction_venture_launch_withdraw(vl.program_id, vl.vault_account, vl.data_account, &mint_info, receive_account, amount)
+    ];
+
+    let transaction_index = match transaction_index {
+        Some(transaction_index) => transaction_index,
+        None => multisig_state.transaction_index + 1
+    };
+
+    let mut steps: Vec<Step> = Vec::new();
+    if is_execute {
+        let ix_execute = multisig
+            .instruction_vault_transaction_execute_for_index(creator_keypair.pubkey(), transaction_index, &vault_instructions, 0)
+            .map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let ix_receiver_ata = create_associated_token_account_idempotent(&creator_keypair.pubkey(), &receiver, &vl.mint, &mint_info.token_program);
+        let tx_execute = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_receiver_ata, ix_execute]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx_execute, vec![&creator_keypair]));
+    } else {
+        let memo = Some(format!("Withdraw {amount} from escrow {}", vl.vault_account));
+        let groups = multisig
+            .instructions_vault_transaction_create(creator_keypair.pubkey(), transaction_index, &vault_instructions, 0, memo)
+            .await
+            .map_err(|err| format!("\"msg\": \"{err}\""))?;
+        for group in groups {
+            let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &group).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            steps.push((tx, vec![&creator_keypair]));
+        }
+
+        let ix_propose = multisig.instruction_proposal_create_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let ix_approve = multisig.instruction_proposal_approve_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let tx_propose = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_propose, ix_approve]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx_propose, vec![&creator_keypair]));
+    }
+
+    let submission = submit(multisig.get_rpc_client(), steps, is_dry_run).await?;
+    if !is_execute && !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "vault").await;
+    }
+
+    Ok(format!(
+        "\"is_execute\":  \"{}\",
+        \"vault_account\":  \"{}\",
+        \"receiver\":  \"{}\",
+        \"amount\":  \"{}\",
+        \"transaction_index\":  {},
+        {submission}",
+        is_execute,
+        vl.vault_account,
+        receiver,
+        amount,
+        transaction_index
+    ))
+}
+
+/// Wraps `amount` lamports of the operator into the wSOL account of the DAO vault, created when missing
+pub async fn fund_vault_with_wsol(
+    multisig_pda: String,
+    amount: u64,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+    let rpc_client = multisig.get_rpc_client();
+    let vault_pda = multisig.get_vault_pda();
+
+    let instructions = instructions_wrap_sol(rpc_client, &creator_keypair.pubkey(), &vault_pda, amount)
+        .await
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &instructions).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let submission = submit(rpc_client, vec![(tx, vec![&creator_keypair])], is_dry_run).await?;
+
+    Ok(format!(
+        "\"vault_pda\":  \"{}\",
+        \"wsol_account\":  \"{}\",
+        \"amount\":  \"{}\",
+        {submission}",
+        vault_pda,
+        get_wrapped_sol_address(&vault_pda),
+        amount
+    ))
+}
+
+/// Proposes a vault transaction closing the wSOL account of the DAO vault, which unwraps everything it holds back to
+/// lamports of the vault. With `transaction_index` the approved proposal is executed
+pub async fn unwrap_vault_wsol(
+    multisig_pda: String,
+    transaction_index: Option<u64>,
+    is_dry_run: bool
+) -> Result<String, String>  {
+    dotenv().ok();
+
+    let creator_keypair = get_ba_keypair().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig_pda = Pubkey::from_str(&multisig_pda).map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let multisig = get_base_multisig(multisig_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let multisig: Arc<&dyn BusinessAnalystMultisigTrait> = Arc::new(&multisig);
+    let rpc_client = multisig.get_rpc_client();
+    let vault_pda = multisig.get_vault_pda();
+    let is_execute = transaction_index.is_some();
+
+    let multisig_state = multisig.get_multisig().await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let required_permissions: &[Permission] = if is_execute { &[Permission::Execute] } else { &[Permission::Initiate, Permission::Vote] };
+    check_member_permissions(&multisig_state, &creator_keypair.pubkey(), required_permissions)?;
+
+    let wsol_balance = get_wrapped_sol_balance(rpc_client, &vault_pda).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+    let vault_instructions = vec![
+        match is_execute {
+            // The proposal was checked when created, the account only has to be closed now
+            true => instruction_close_wrapped_sol_account(&vault_pda, &vault_pda),
+            false => instruction_unwrap_sol(rpc_client, &vault_pda, &vault_pda).await
+        }.map_err(|err| format!("\"msg\": \"{err}\""))?
+    ];
+
+    let transaction_index = match transaction_index {
+        Some(transaction_index) => transaction_index,
+        None => multisig_state.transaction_index + 1
+    };
+
+    let mut steps: Vec<Step> = Vec::new();
+    if is_execute {
+        let ix_execute = multisig
+            .instruction_vault_transaction_execute_for_index(creator_keypair.pubkey(), transaction_index, &vault_instructions, 0)
+            .map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let tx_execute = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_execute]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx_execute, vec![&creator_keypair]));
+    } else {
+        let memo = Some(format!("Unwrap wSOL of {vault_pda}"));
+        let groups = multisig
+            .instructions_vault_transaction_create(creator_keypair.pubkey(), transaction_index, &vault_instructions, 0, memo)
+            .await
+            .map_err(|err| format!("\"msg\": \"{err}\""))?;
+        for group in groups {
+            let tx = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &group).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+            steps.push((tx, vec![&creator_keypair]));
+        }
+
+        let ix_propose = multisig.instruction_proposal_create_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let ix_approve = multisig.instruction_proposal_approve_for_index(creator_keypair.pubkey(), transaction_index).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        let tx_propose = multisig.get_transaction_from_instructions(creator_keypair.pubkey(), &[ix_propose, ix_approve]).await.map_err(|err| format!("\"msg\": \"{err}\""))?;
+        steps.push((tx_propose, vec![&creator_keypair]));
+    }
+
+    let submission = submit(rpc_client, steps, is_dry_run).await?;
+    if !is_execute && !is_dry_run {
+        record_proposal(&multisig_pda, transaction_index, "vault").await;
+    }
+
+    Ok(format!(
+        "\"is_execute\":  \"{}\",
+        \"vault_pda\":  \"{}\",
+        \"wsol_account\":  \"{}\",
+        \"wsol_balance\":  \"{}\",
+        \"transaction_index\":  {},
+        {submission}",
+        is_execute,
+        vault_pda,
+        get_wrapped_sol_address(&vault_pda),
+        wsol_balance,
+        transaction_index
+    ))
+}
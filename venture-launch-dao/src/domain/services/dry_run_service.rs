@@ -0,0 +1,192 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_sdk::message::Message;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+
+use super::error::PreflightError;
+use super::instruction_decoder::{decode_message, DecodedInstruction};
+
+/// A transaction exactly as it would have been sent
+#[derive(Debug, Clone)]
+pub struct DryRunTransaction {
+    pub base64: String,
+    pub fee: u64,
+    pub instructions: Vec<DecodedInstruction>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountChange {
+    pub pubkey: Pubkey,
+    pub lamports_before: u64,
+    pub lamports_after: u64,
+    pub data_len_before: u64,
+    pub data_len_after: u64,
+    pub owner_after: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DryRunReport {
+    pub transactions: Vec<DryRunTransaction>,
+    pub account_changes: Vec<AccountChange>,
+    pub fee: u64,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl DryRunReport {
+    pub fn to_response(&self) -> String {
+        let transactions = self.transactions.iter().map(|transaction| serde_json::json!({
+            "base64": transaction.base64,
+            "fee": transaction.fee,
+            "instructions": transaction.instructions.iter().map(DecodedInstruction::to_json).collect::<Vec<serde_json::Value>>(),
+        })).collect::<Vec<serde_json::Value>>();
+
+        let account_changes = self.account_changes.iter().map(|change| serde_json::json!({
+            "pubkey": change.pubkey.to_string(),
+            "lamports_before": change.lamports_before,
+            "lamports_after": change.lamports_after,
+            "data_len_before": change.data_len_before,
+            "data_len_after": change.data_len_after,
+            "owner": change.owner_after,
+        })).collect::<Vec<serde_json::Value>>();
+
+        format!(
+            "\"dry_run\":  true,
+            \"fee\":  {},
+            \"units_consumed\":  {},
+            \"error\":  {},
+            \"transactions\":  {},
+            \"account_changes\":  {},
+            \"logs\":  {}",
+            self.fee,
+            serde_json::json!(self.units_consumed),
+            serde_json::json!(self.error),
+            serde_json::Value::Array(transactions),
+            serde_json::Value::Array(account_changes),
+            serde_json::json!(self.logs)
+        )
+    }
+}
+
+/// Encodes and decodes every transaction of a request and simulates them together,
+/// so the later transactions see the accounts the earlier ones would have created.
+/// Nothing is sent, a failed simulation is reported in `error` instead of failing the request
+pub async fn dry_run(rpc_client: &RpcClient, transactions: &[Transaction]) -> Result<DryRunReport, PreflightError> {
+    let mut report = DryRunReport::default();
+
+    for transaction in transactions {
+        let serialized = bincode::serialize(transaction)
+            .map_err(|err| PreflightError::FailedToSimulateTransaction(err.to_string()))?;
+        let fee = rpc_client
+            .get_fee_for_message(&transaction.message)
+            .await
+            .map_err(|_| PreflightError::FailedToFetchFee)?;
+
+        report.fee += fee;
+        report.transactions.push(DryRunTransaction {
+            base64: BASE64.encode(serialized),
+            fee,
+            instructions: decode_message(&transaction.message),
+        });
+    }
+
+    let simulated = match combine_transactions(transactions) {
+        Some(simulated) => simulated,
+        None => return Ok(report)
+    };
+
+    let writable_accounts: Vec<Pubkey> = simulated
+        .message
+        .account_keys
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| simulated.message.is_writable(*index))
+        .map(|(_, pubkey)| *pubkey)
+        .collect();
+
+    let accounts_before = rpc_client
+        .get_multiple_accounts(&writable_accounts)
+        .await
+        .map_err(|err| PreflightError::FailedToSimulateTransaction(err.to_string()))?;
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: None,
+            addresses: writable_accounts.iter().map(Pubkey::to_string).collect(),
+        }),
+        ..RpcSimulateTransactionConfig::default()
+    };
+
+    let simulation = rpc_client
+        .simulate_transaction_with_config(&simulated, config)
+        .await
+        .map_err(|err| PreflightError::FailedToSimulateTransaction(err.to_string()))?
+        .value;
+
+    report.units_consumed = simulation.units_consumed;
+    report.logs = simulation.logs.unwrap_or_default();
+    report.error = simulation.err.map(|err| err.to_string());
+
+    let accounts_after = simulation.accounts.unwrap_or_default();
+
+    report.account_changes = writable_accounts
+        .iter()
+        .enumerate()
+        .map(|(index, pubkey)| {
+            let before = accounts_before.get(index).cloned().flatten();
+            let after = accounts_after.get(index).cloned().flatten();
+
+            AccountChange {
+                pubkey: *pubkey,
+                lamports_before: before.as_ref().map(|account| account.lamports).unwrap_or(0),
+                lamports_after: after.as_ref().map(|account| account.lamports).unwrap_or(0),
+                data_len_before: before.as_ref().map(|account| account.data.len() as u64).unwrap_or(0),
+                data_len_after: after.as_ref().and_then(|account| account.space).unwrap_or(0),
+                owner_after: after.map(|account| account.owner),
+            }
+        })
+        .filter(|change| {
+            change.lamports_before != change.lamports_after || change.data_len_before != change.data_len_after
+        })
+        .collect();
+
+    Ok(report)
+}
+
+/// Merges the instructions of all transactions into one paid by the first fee payer,
+/// falls back to the first transaction when the merged one would not fit in a packet
+fn combine_transactions(transactions: &[Transaction]) -> Option<Transaction> {
+    let first = transactions.first()?;
+    if transactions.len() == 1 {
+        return Some(first.clone());
+    }
+
+    let payer = first.message.account_keys[0];
+    let instructions = transactions
+        .iter()
+        .flat_map(|transaction| {
+            decode_message(&transaction.message)
+                .into_iter()
+                // Every step carries its own compute budget, duplicates would fail the combined simulation
+                .filter(|decoded| decoded.program_id != solana_sdk::compute_budget::id())
+                .map(|decoded| solana_sdk::instruction::Instruction {
+                    program_id: decoded.program_id,
+                    accounts: decoded.accounts,
+                    data: decoded.data,
+                })
+        })
+        .collect::<Vec<_>>();
+
+    let combined = Transaction::new_unsigned(Message::new(&instructions, Some(&payer)));
+
+    match bincode::serialized_size(&combined) {
+        Ok(size) if size as usize <= PACKET_DATA_SIZE => Some(combined),
+        _ => Some(first.clone())
+    }
+}
@@ -0,0 +1,170 @@
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone)]
+pub enum PreflightError {
+    #[error("Failed to simulate transaction: {0}")]
+    FailedToSimulateTransaction(String),
+    #[error("Transaction simulation failed: {err}")]
+    SimulationFailed { err: String, logs: Vec<String> },
+    #[error("Failed to fetch fee for message")]
+    FailedToFetchFee,
+    #[error("Failed to fetch balance of {0}")]
+    FailedToFetchBalance(Pubkey),
+    #[error("Fee payer {payer} holds {balance} lamports but {required} are required")]
+    InsufficientFeePayerBalance { payer: Pubkey, balance: u64, required: u64, logs: Vec<String> },
+    #[error("Fee payer {payer} holding {balance} lamports can not pay rent for the new accounts")]
+    InsufficientFundsForRent { payer: Pubkey, balance: u64, logs: Vec<String> },
+    #[error("Failed to fetch multisig config account")]
+    FailedToFetchMultisig,
+    #[error("{member} is not a member of the multisig")]
+    NotAMember { member: Pubkey },
+    #[error("{member} does not have {permission} permission")]
+    MissingPermission { member: Pubkey, permission: &'static str },
+    #[error("Failed to fetch token account {0}")]
+    FailedToFetchTokenAccount(Pubkey),
+    #[error("Vault token account {account} holds {balance} but {required} are required")]
+    InsufficientVaultBalance { account: Pubkey, balance: u64, required: u64 },
+    #[error("Failed to fetch recent prioritization fees")]
+    FailedToFetchPrioritizationFees,
+    #[error("Invalid compute budget config value {0}")]
+    InvalidComputeBudgetConfig(String),
+}
+
+impl PreflightError {
+    pub fn logs(&self) -> &[String] {
+        match self {
+            Self::SimulationFailed { logs, .. } => logs,
+            Self::InsufficientFeePayerBalance { logs, .. } => logs,
+            Self::InsufficientFundsForRent { logs, .. } => logs,
+            _ => &[]
+        }
+    }
+
+    /// Response body in the same `"msg": ...` shape the services return, with the simulation logs attached
+    pub fn to_response(&self) -> String {
+        format!(
+            "\"msg\": {},
+            \"logs\": {}",
+            serde_json::to_string(&self.to_string()).unwrap_or_default(),
+            serde_json::to_string(self.logs()).unwrap_or_default()
+        )
+    }
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum SendError {
+    #[error("Failed to fetch latest blockhash: {0}")]
+    FailedToFetchBlockhash(String),
+    #[error("Failed to sign transaction: {0}")]
+    FailedToSign(String),
+    #[error("Failed to fetch transaction status: {0}")]
+    FailedToFetchStatus(String),
+    #[error("Invalid sender config value {0}")]
+    InvalidSenderConfig(String),
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum NonceError {
+    #[error("Failed to fetch nonce account {0}")]
+    FailedToFetchNonceAccount(Pubkey),
+    #[error("{0} is not an initialized nonce account")]
+    InvalidNonceAccount(Pubkey),
+    #[error("Nonce account {nonce_account} is controlled by {authority}")]
+    WrongNonceAuthority { nonce_account: Pubkey, authority: Pubkey },
+    #[error("Failed to fetch rent exemption for nonce account")]
+    FailedToFetchRent,
+    #[error("Transaction does not advance the member nonce account first")]
+    NotANonceTransaction,
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum RefundError {
+    #[error("No investors to refund")]
+    NoInvestors,
+    #[error("Investor weights add up to zero")]
+    ZeroTotalWeight,
+    #[error("Investor {0} is listed more than once")]
+    DuplicateInvestor(Pubkey),
+    #[error("Failed to fetch holders of {0}")]
+    FailedToFetchSnapshot(Pubkey),
+    #[error("Vault token account {0} is empty")]
+    EmptyVault(Pubkey),
+    #[error("Vault holds {balance} lamports but {required} are required for rent of new token accounts")]
+    InsufficientVaultLamports { balance: u64, required: u64 },
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum EscrowError {
+    #[error("Vault holds {balance} lamports but {required} are required for rent of the escrow accounts")]
+    InsufficientVaultLamports { balance: u64, required: u64 },
+    #[error("Escrow {vault_account} is controlled by {authority}, not the DAO vault")]
+    NotVaultAuthority { vault_account: Pubkey, authority: Pubkey },
+    #[error("Escrow {vault_account} holds {balance} but {required} are required")]
+    InsufficientEscrowBalance { vault_account: Pubkey, balance: u64, required: u64 },
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum AuditError {
+    #[error("Failed to fetch signatures of {0}")]
+    FailedToFetchSignatures(Pubkey),
+    #[error("Failed to fetch transaction {0}")]
+    FailedToFetchTransaction(Signature),
+    #[error("Failed to decode transaction {0}")]
+    FailedToDecodeTransaction(Signature),
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum BalanceError {
+    #[error("Failed to fetch balance of {0}")]
+    FailedToFetchBalance(Pubkey),
+    #[error("Failed to fetch token accounts of {owner} under {token_program}")]
+    FailedToFetchTokenAccounts { owner: Pubkey, token_program: Pubkey },
+    #[error("Failed to fetch mints")]
+    FailedToFetchMints,
+    #[error("{0} is not a mint")]
+    InvalidMint(Pubkey),
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum RentError {
+    #[error("Multisig has no rent collector, set one with batch_config set_rent_collector first")]
+    RentCollectorNotSet,
+    #[error("Failed to fetch transaction accounts")]
+    FailedToFetchTransactions,
+    #[error("Failed to deserialize proposal of transaction {0}")]
+    FailedToDeserializeProposal(u64),
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum StoreError {
+    #[error("Failed to open store: {0}")]
+    FailedToOpenStore(String),
+    #[error("Failed to apply store migration {version}: {err}")]
+    FailedToMigrate { version: usize, err: String },
+    #[error("Store query failed: {0}")]
+    QueryFailed(String),
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum IndexerError {
+    #[error("Failed to fetch accounts of {0}")]
+    FailedToFetchAccounts(Pubkey),
+    #[error("Multisig {0} does not exist")]
+    MultisigNotFound(Pubkey),
+    #[error("Failed to deserialize proposal of transaction {0}")]
+    FailedToDeserializeProposal(u64),
+    #[error("Failed to access indexer cursor: {0}")]
+    FailedToAccessCursor(String),
+    #[error("Failed to publish event: {0}")]
+    FailedToPublish(String),
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum ConfirmationError {
+    #[error("Invalid confirmation config value {0}")]
+    InvalidConfirmationConfig(String),
+    #[error("Failed to fetch status of {0}")]
+    FailedToFetchStatus(Signature),
+}
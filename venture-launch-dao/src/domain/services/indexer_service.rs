@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::state::Account as TokenAccount;
+use squads_multisig::anchor_lang::AccountDeserialize;
+use squads_multisig::pda::{get_proposal_pda, get_vault_pda};
+use squads_multisig::squads_multisig_program::{self, Multisig};
+use squads_multisig::state::{Proposal, ProposalStatus};
+
+use super::error::IndexerError;
+
+/// What the indexer last saw of a DAO. Stored as the resume cursor, events are the difference between two snapshots
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct DaoSnapshot {
+    pub slot: u64,
+    pub threshold: u16,
+    /// Member pubkey to permissions mask
+    pub members: BTreeMap<String, u8>,
+    pub transaction_index: u64,
+    pub proposals: BTreeMap<u64, ProposalSnapshot>,
+    pub vault_lamports: u64,
+    pub vault_token_balance: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProposalSnapshot {
+    pub status: String,
+    pub approved: Vec<String>,
+    pub rejected: Vec<String>,
+    pub cancelled: Vec<String>,
+}
+
+impl ProposalSnapshot {
+    /// A proposal in a final status never changes again and is not fetched anymore
+    fn is_final(&self) -> bool {
+        matches!(self.status.as_str(), "executed" | "rejected" | "cancelled")
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DaoEventKind {
+    ProposalCreated { transaction_index: u64, status: String },
+    ProposalVoted { transaction_index: u64, member: String, vote: String },
+    ProposalStatusChanged { transaction_index: u64, from: String, to: String },
+    MemberAdded { member: String, permissions: u8 },
+    MemberRemoved { member: String },
+    MemberPermissionsChanged { member: String, from: u8, to: u8 },
+    ThresholdChanged { from: u16, to: u16 },
+    /// `asset` is `sol` for the vault lamports or the mint of the vault token account
+    VaultBalanceChanged { asset: String, from: u64, to: u64 },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct DaoEvent {
+    /// Same change always gets the same id, so consumers can drop events delivered twice
+    pub id: String,
+    pub multisig_pda: String,
+    pub slot: u64,
+    #[serde(flatten)]
+    pub kind: DaoEventKind,
+}
+
+/// Events turning `previous` into `current`
+pub fn diff_snapshots(multisig_pda: &Pubkey, token_mint: &Pubkey, previous: &DaoSnapshot, current: &DaoSnapshot) -> Vec<DaoEvent> {
+    let mut kinds = Vec::new();
+
+    if previous.threshold != current.threshold {
+        kinds.push((
+            format!("threshold:{}:{}", current.threshold, current.transaction_index),
+            DaoEventKind::ThresholdChanged { from: previous.threshold, to: current.threshold },
+        ));
+    }
+
+    for (member, permissions) in &current.members {
+        match previous.members.get(member) {
+            None => kinds.push((
+                format!("member_added:{member}:{}", current.transaction_index),
+                DaoEventKind::MemberAdded { member: member.clone(), permissions: *permissions },
+            )),
+            Some(from) if from != permissions => kinds.push((
+                format!("member_permissions:{member}:{permissions}:{}", current.transaction_index),
+                DaoEventKind::MemberPermissionsChanged { member: member.clone(), from: *from, to: *permissions },
+            )),
+            Some(_) => {}
+        }
+    }
+    for member in previous.members.keys().filter(|member| !current.members.contains_key(*member)) {
+        kinds.push((
+            format!("member_removed:{member}:{}", current.transaction_index),
+            DaoEventKind::MemberRemoved { member: member.clone() },
+        ));
+    }
+
+    for (transaction_index, proposal) in &current.proposals {
+        let before = previous.proposals.get(transaction_index);
+
+        match before {
+            None => kinds.push((
+                format!("proposal_created:{transaction_index}"),
+                DaoEventKind::ProposalCreated { transaction_index: *transaction_index, status: proposal.status.clone() },
+            )),
+            Some(before) if before.status != proposal.status => kinds.push((
+                format!("proposal_status:{transaction_index}:{}", proposal.status),
+                DaoEventKind::ProposalStatusChanged {
+                    transaction_index: *transaction_index,
+                    from: before.status.clone(),
+                    to: proposal.status.clone(),
+                },
+            )),
+            Some(_) => {}
+        }
+
+        for (vote, voters, voted_before) in [
+            ("approve", &proposal.approved, before.map(|before| &before.approved)),
+            ("reject", &proposal.rejected, before.map(|before| &before.rejected)),
+            ("cancel", &proposal.cancelled, before.map(|before| &before.cancelled)),
+        ] {
+            for member in voters.iter().filter(|member| !voted_before.map(|voted| voted.contains(member)).unwrap_or(false)) {
+                kinds.push((
+                    format!("proposal_voted:{transaction_index}:{member}:{vote}"),
+                    DaoEventKind::ProposalVoted { transaction_index: *transaction_index, member: member.clone(), vote: vote.to_string() },
+                ));
+            }
+        }
+    }
+
+    if previous.vault_lamports != current.vault_lamports {
+        kinds.push((
+            format!("vault_balance:sol:{}:{}", current.vault_lamports, current.slot),
+            DaoEventKind::VaultBalanceChanged { asset: "sol".to_string(), from: previous.vault_lamports, to: current.vault_lamports },
+        ));
+    }
+    if previous.vault_token_balance != current.vault_token_balance {
+        kinds.push((
+            format!("vault_balance:{token_mint}:{}:{}", current.vault_token_balance, current.slot),
+            DaoEventKind::VaultBalanceChanged {
+                asset: token_mint.to_string(),
+                from: previous.vault_token_balance,
+                to: current.vault_token_balance,
+            },
+        ));
+    }
+
+    kinds
+        .into_iter()
+        .map(|(key, kind)| DaoEvent {
+            id: format!("{multisig_pda}:{key}"),
+            multisig_pda: multisig_pda.to_string(),
+            slot: current.slot,
+            kind,
+        })
+        .collect()
+}
+
+/// Reads the current state of a DAO. Proposals already final in `previous` are carried over without fetching them
+pub async fn get_dao_snapshot(
+    rpc_client: &RpcClient,
+    multisig_pda: &Pubkey,
+    token_mint: &Pubkey,
+    previous: Option<&DaoSnapshot>
+) -> Result<DaoSnapshot, IndexerError> {
+    let program_id = squads_multisig_program::ID;
+    let (vault_pda, _) = get_vault_pda(multisig_pda, 0, Some(&program_id));
+    let vault_token_account = get_associated_token_address(&vault_pda, token_mint);
+
+    let response = rpc_client
+        .get_multiple_accounts_with_commitment(&[*multisig_pda, vault_pda, vault_token_account], rpc_client.commitment())
+        .await
+        .map_err(|_| IndexerError::FailedToFetchAccounts(*multisig_pda))?;
+    let slot = response.context.slot;
+    let [multisig, vault, vault_token] = <[_; 3]>::try_from(response.value).map_err(|_| IndexerError::FailedToFetchAccounts(*multisig_pda))?;
+
+    let multisig = multisig.ok_or(IndexerError::MultisigNotFound(*multisig_pda))?;
+    let multisig = Multisig::try_deserialize(&mut multisig.data.as_slice()).map_err(|_| IndexerError::MultisigNotFound(*multisig_pda))?;
+
+    let mut proposals: BTreeMap<u64, ProposalSnapshot> = BTreeMap::new();
+    let mut indexes = Vec::new();
+    for transaction_index in 1..=multisig.transaction_index {
+        match previous.and_then(|previous| previous.proposals.get(&transaction_index)) {
+            Some(proposal) if proposal.is_final() => { proposals.insert(transaction_index, proposal.clone()); },
+            // Indexes below the previous cursor without a proposal never had one or were closed
+            None if previous.map(|previous| transaction_index <= previous.transaction_index).unwrap_or(false) => {},
+            _ => indexes.push(transaction_index)
+        }
+    }
+
+    for chunk in indexes.chunks(100) {
+        let addresses: Vec<Pubkey> = chunk
+            .iter()
+            .map(|transaction_index| get_proposal_pda(multisig_pda, *transaction_index, Some(&program_id)).0)
+            .collect();
+        let accounts = rpc_client
+            .get_multiple_accounts(&addresses)
+            .await
+            .map_err(|_| IndexerError::FailedToFetchAccounts(*multisig_pda))?;
+
+        for (transaction_index, account) in chunk.iter().zip(accounts) {
+            let Some(account) = account else { continue };
+            let proposal = Proposal::try_deserialize(&mut account.data.as_slice())
+                .map_err(|_| IndexerError::FailedToDeserializeProposal(*transaction_index))?;
+
+            proposals.insert(*transaction_index, ProposalSnapshot {
+                status: proposal_status_name(&proposal.status).to_string(),
+                approved: proposal.approved.iter().map(Pubkey::to_string).collect(),
+                rejected: proposal.rejected.iter().map(Pubkey::to_string).collect(),
+                cancelled: proposal.cancelled.iter().map(Pubkey::to_string).collect(),
+            });
+        }
+    }
+
+    Ok(DaoSnapshot {
+        slot,
+        threshold: multisig.threshold,
+        members: multisig.members.iter().map(|member| (member.key.to_string(), member.permissions.mask)).collect(),
+        transaction_index: multisig.transaction_index,
+        proposals,
+        vault_lamports: vault.map(|vault| vault.lamports).unwrap_or(0),
+        vault_token_balance: vault_token
+            .and_then(|account| TokenAccount::unpack(&account.data).ok())
+            .map(|account| account.amount)
+            .unwrap_or(0),
+    })
+}
+
+pub fn proposal_status_name(status: &ProposalStatus) -> &'static str {
+    match status {
+        ProposalStatus::Draft { .. } => "draft",
+        ProposalStatus::Active { .. } => "active",
+        ProposalStatus::Rejected { .. } => "rejected",
+        ProposalStatus::Approved { .. } => "approved",
+        #[allow(deprecated)]
+        ProposalStatus::Executing => "executing",
+        ProposalStatus::Executed { .. } => "executed",
+        ProposalStatus::Cancelled { .. } => "cancelled",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proposal(status: &str, approved: &[&str]) -> ProposalSnapshot {
+        ProposalSnapshot {
+            status: status.to_string(),
+            approved: approved.iter().map(|member| member.to_string()).collect(),
+            rejected: vec![],
+            cancelled: vec![],
+        }
+    }
+
+    #[test]
+    fn same_snapshot_gives_no_events() {
+        let snapshot = DaoSnapshot {
+            threshold: 2,
+            members: BTreeMap::from([("a".to_string(), 7)]),
+            proposals: BTreeMap::from([(1, proposal("active", &["a"]))]),
+            ..DaoSnapshot::default()
+        };
+
+        assert!(diff_snapshots(&Pubkey::new_unique(), &Pubkey::new_unique(), &snapshot, &snapshot).is_empty());
+    }
+
+    #[test]
+    fn votes_and_status_changes_are_reported() {
+        let multisig_pda = Pubkey::new_unique();
+        let previous = DaoSnapshot {
+            transaction_index: 1,
+            proposals: BTreeMap::from([(1, proposal("active", &["a"]))]),
+            ..DaoSnapshot::default()
+        };
+        let current = DaoSnapshot {
+            transaction_index: 2,
+            proposals: BTreeMap::from([(1, proposal("approved", &["a", "b"])), (2, proposal("active", &[]))]),
+            ..DaoSnapshot::default()
+        };
+
+        let kinds: Vec<DaoEventKind> = diff_snapshots(&multisig_pda, &Pubkey::new_unique(), &previous, &current)
+            .into_iter()
+            .map(|event| event.kind)
+            .collect();
+
+        assert_eq!(vec![
+            DaoEventKind::ProposalStatusChanged { transaction_index: 1, from: "active".into(), to: "approved".into() },
+            DaoEventKind::ProposalVoted { transaction_index: 1, member: "b".into(), vote: "approve".into() },
+            DaoEventKind::ProposalCreated { transaction_index: 2, status: "active".into() },
+        ], kinds);
+    }
+
+    #[test]
+    fn membership_threshold_and_balance_changes_are_reported() {
+        let token_mint = Pubkey::new_unique();
+        let previous = DaoSnapshot {
+            threshold: 1,
+            members: BTreeMap::from([("a".to_string(), 7), ("b".to_string(), 2)]),
+            vault_token_balance: 10,
+            ..DaoSnapshot::default()
+        };
+        let current = DaoSnapshot {
+            threshold: 2,
+            members: BTreeMap::from([("a".to_string(), 7), ("c".to_string(), 2)]),
+            vault_token_balance: 4,
+            ..DaoSnapshot::default()
+        };
+
+        let kinds: Vec<DaoEventKind> = diff_snapshots(&Pubkey::new_unique(), &token_mint, &previous, &current)
+            .into_iter()
+            .map(|event| event.kind)
+            .collect();
+
+        assert_eq!(vec![
+            DaoEventKind::ThresholdChanged { from: 1, to: 2 },
+            DaoEventKind::MemberAdded { member: "c".into(), permissions: 2 },
+            DaoEventKind::MemberRemoved { member: "b".into() },
+            DaoEventKind::VaultBalanceChanged { asset: token_mint.to_string(), from: 10, to: 4 },
+        ], kinds);
+    }
+
+    #[test]
+    fn event_ids_are_stable() {
+        let multisig_pda = Pubkey::new_unique();
+        let token_mint = Pubkey::new_unique();
+        let previous = DaoSnapshot::default();
+        let current = DaoSnapshot {
+            transaction_index: 1,
+            proposals: BTreeMap::from([(1, proposal("active", &["a"]))]),
+            ..DaoSnapshot::default()
+        };
+
+        let first = diff_snapshots(&multisig_pda, &token_mint, &previous, &current);
+        let second = diff_snapshots(&multisig_pda, &token_mint, &previous, &DaoSnapshot { slot: 99, ..current });
+
+        assert_eq!(
+            first.iter().map(|event| &event.id).collect::<Vec<_>>(),
+            second.iter().map(|event| &event.id).collect::<Vec<_>>()
+        );
+        assert_eq!(format!("{multisig_pda}:proposal_created:1"), first[0].id);
+    }
+}
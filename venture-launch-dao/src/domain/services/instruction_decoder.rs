@@ -0,0 +1,183 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use solana_sdk::{
+    compute_budget, instruction::AccountMeta, message::Message, program_utils::limited_deserialize, pubkey::Pubkey,
+    system_instruction::SystemInstruction, system_program,
+};
+use spl_token::instruction::TokenInstruction;
+use crate::contract_module::instruction::VentureLaunchInstruction;
+use crate::contract_module::venture_launch::get_program_id;
+use squads_multisig::{
+    anchor_lang::Discriminator,
+    squads_multisig_program::{self, instruction as squads_instruction},
+};
+
+/// Instruction of a compiled message with its accounts resolved and a human readable name
+#[derive(Debug, Clone)]
+pub struct DecodedInstruction {
+    pub program_id: Pubkey,
+    pub name: String,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl DecodedInstruction {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "program_id": self.program_id.to_string(),
+            "name": self.name,
+            "accounts": self.accounts.iter().map(|meta| serde_json::json!({
+                "pubkey": meta.pubkey.to_string(),
+                "is_signer": meta.is_signer,
+                "is_writable": meta.is_writable,
+            })).collect::<Vec<serde_json::Value>>(),
+            "data": BASE64.encode(&self.data),
+        })
+    }
+}
+
+pub fn decode_message(message: &Message) -> Vec<DecodedInstruction> {
+    message
+        .instructions
+        .iter()
+        .map(|instruction| {
+            let program_id = message.account_keys[instruction.program_id_index as usize];
+            let accounts = instruction
+                .accounts
+                .iter()
+                .map(|index| {
+                    let index = *index as usize;
+                    AccountMeta {
+                        pubkey: message.account_keys[index],
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_writable(index),
+                    }
+                })
+                .collect();
+
+            DecodedInstruction {
+                program_id,
+                name: instruction_name(&program_id, &instruction.data),
+                accounts,
+                data: instruction.data.clone(),
+            }
+        })
+        .collect()
+}
+
+pub fn instruction_name(program_id: &Pubkey, data: &[u8]) -> String {
+    if *program_id == squads_multisig_program::ID {
+        return squads_instruction_name(data).unwrap_or("Unknown").to_string();
+    }
+
+    if *program_id == system_program::ID {
+        return match limited_deserialize::<SystemInstruction>(data) {
+            Ok(instruction) => variant_name(&instruction),
+            Err(_) => "Unknown".to_string()
+        };
+    }
+
+    if *program_id == spl_token::id() {
+        return match TokenInstruction::unpack(data) {
+            Ok(instruction) => variant_name(&instruction),
+            Err(_) => "Unknown".to_string()
+        };
+    }
+
+    if *program_id == spl_associated_token_account::id() {
+        return match data.first() {
+            None | Some(0) => "Create",
+            Some(1) => "CreateIdempotent",
+            Some(2) => "RecoverNested",
+            Some(_) => "Unknown",
+        }.to_string();
+    }
+
+    if *program_id == get_program_id() {
+        return match VentureLaunchInstruction::unpack(data) {
+            Ok(instruction) => instruction.name().to_string(),
+            Err(_) => "Unknown".to_string()
+        };
+    }
+
+    if *program_id == compute_budget::id() {
+        return match data.first() {
+            Some(1) => "RequestHeapFrame",
+            Some(2) => "SetComputeUnitLimit",
+            Some(3) => "SetComputeUnitPrice",
+            Some(4) => "SetLoadedAccountsDataSizeLimit",
+            _ => "Unknown",
+        }.to_string();
+    }
+
+    "Unknown".to_string()
+}
+
+fn squads_instruction_name(data: &[u8]) -> Option<&'static str> {
+    let discriminator: [u8; 8] = data.get(..8)?.try_into().ok()?;
+
+    let known: [([u8; 8], &'static str); 10] = [
+        (squads_instruction::MultisigCreateV2::DISCRIMINATOR, "MultisigCreateV2"),
+        (squads_instruction::ConfigTransactionCreate::DISCRIMINATOR, "ConfigTransactionCreate"),
+        (squads_instruction::ConfigTransactionExecute::DISCRIMINATOR, "ConfigTransactionExecute"),
+        (squads_instruction::VaultTransactionCreate::DISCRIMINATOR, "VaultTransactionCreate"),
+        (squads_instruction::VaultTransactionExecute::DISCRIMINATOR, "VaultTransactionExecute"),
+        (squads_instruction::ProposalCreate::DISCRIMINATOR, "ProposalCreate"),
+        (squads_instruction::ProposalApprove::DISCRIMINATOR, "ProposalApprove"),
+        (squads_instruction::ProposalReject::DISCRIMINATOR, "ProposalReject"),
+        (squads_instruction::ProposalCancel::DISCRIMINATOR, "ProposalCancel"),
+        (squads_instruction::SpendingLimitUse::DISCRIMINATOR, "SpendingLimitUse"),
+    ];
+
+    known
+        .iter()
+        .find(|(known_discriminator, _)| *known_discriminator == discriminator)
+        .map(|(_, name)| *name)
+}
+
+/// `Transfer { lamports: 1 }` -> `Transfer`
+fn variant_name<T: std::fmt::Debug>(value: &T) -> String {
+    let debug = format!("{:?}", value);
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{compute_budget::ComputeBudgetInstruction, system_instruction};
+
+    #[test]
+    fn decodes_system_and_compute_budget_instructions() {
+        let payer = Pubkey::new_unique();
+        let message = Message::new(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+                system_instruction::transfer(&payer, &Pubkey::new_unique(), 1),
+            ],
+            Some(&payer),
+        );
+
+        let decoded = decode_message(&message);
+
+        assert_eq!("SetComputeUnitLimit", decoded[0].name);
+        assert_eq!("Transfer", decoded[1].name);
+        assert!(decoded[1].accounts[0].is_signer);
+        assert!(decoded[1].accounts[1].is_writable);
+    }
+
+    #[test]
+    fn decodes_venture_launch_instructions() {
+        let data = VentureLaunchInstruction::Withdraw { amount: 10 }.pack();
+
+        assert_eq!("Withdraw", instruction_name(&get_program_id(), &data));
+        assert_eq!("Unknown", instruction_name(&get_program_id(), &[9]));
+    }
+
+    #[test]
+    fn unknown_program_is_reported_as_unknown() {
+        assert_eq!("Unknown", instruction_name(&Pubkey::new_unique(), &[1, 2, 3]));
+    }
+}
@@ -0,0 +1,147 @@
+use solana_sdk::pubkey::Pubkey;
+
+use super::store_service::{get_store, Store};
+use crate::domain::entities::milestone::error::MilestoneError;
+use crate::domain::entities::milestone::entity::Milestone;
+
+/// Milestones of every DAO, kept in the service store so their state survives restarts
+#[derive(Clone)]
+pub struct MilestoneStore {
+    store: Store,
+}
+
+impl MilestoneStore {
+    pub fn new(store: Store) -> Self {
+        Self { store }
+    }
+
+    /// Milestones in the store at `STORE_PATH`
+    pub async fn open() -> Result<Self, MilestoneError> {
+        let store = get_store().await.map_err(|err| MilestoneError::FailedToReadStore(err.to_string()))?;
+
+        Ok(Self::new(store.clone()))
+    }
+
+    pub async fn get_milestones(&self, multisig_pda: &Pubkey) -> Result<Vec<Milestone>, MilestoneError> {
+        self.store.get_milestones(multisig_pda).await.map_err(|err| MilestoneError::FailedToReadStore(err.to_string()))
+    }
+
+    /// Runs `update` on the milestones of `multisig_pda` and writes them back when it succeeds
+    pub async fn update<T: Send + 'static>(
+        &self,
+        multisig_pda: &Pubkey,
+        update: impl FnOnce(&mut Vec<Milestone>) -> Result<T, MilestoneError> + Send + 'static
+    ) -> Result<T, MilestoneError> {
+        self.store
+            .update_milestones(multisig_pda, update)
+            .await
+            .map_err(|err| MilestoneError::FailedToWriteStore(err.to_string()))?
+    }
+
+    pub async fn add_milestone(&self, multisig_pda: &Pubkey, milestone: Milestone) -> Result<(), MilestoneError> {
+        self.update(multisig_pda, move |milestones| {
+            if milestones.iter().any(|existing| existing.id == milestone.id) {
+                return Err(MilestoneError::DuplicateMilestone(milestone.id));
+            }
+            milestones.push(milestone);
+
+            Ok(())
+        }).await
+    }
+
+    /// Runs `update` on the milestone `id` of `multisig_pda` and writes it back when it succeeds
+    pub async fn update_milestone<T: Send + 'static>(
+        &self,
+        multisig_pda: &Pubkey,
+        id: &str,
+        update: impl FnOnce(&mut Milestone) -> Result<T, MilestoneError> + Send + 'static
+    ) -> Result<T, MilestoneError> {
+        let id = id.to_string();
+
+        self.update(multisig_pda, move |milestones| {
+            let milestone = milestones
+                .iter_mut()
+                .find(|milestone| milestone.id == id)
+                .ok_or(MilestoneError::UnknownMilestone(id))?;
+
+            update(milestone)
+        }).await
+    }
+
+    /// Runs `update` on the milestone `id` like `update_milestone` before the transactions it stands for are sent,
+    /// so a concurrent withdraw of the milestone fails its checks. Returns the milestone as it was, for `restore_milestone`
+    pub async fn reserve_milestone(
+        &self,
+        multisig_pda: &Pubkey,
+        id: &str,
+        update: impl FnOnce(&mut Milestone) -> Result<(), MilestoneError> + Send + 'static
+    ) -> Result<Milestone, MilestoneError> {
+        self.update_milestone(multisig_pda, id, move |milestone| {
+            let previous = milestone.clone();
+            update(milestone)?;
+
+            Ok(previous)
+        }).await
+    }
+
+    /// Puts back a milestone returned by `reserve_milestone` when its transactions did not land
+    pub async fn restore_milestone(&self, multisig_pda: &Pubkey, previous: Milestone) -> Result<(), MilestoneError> {
+        let id = previous.id.clone();
+
+        self.update_milestone(multisig_pda, &id, move |milestone| {
+            *milestone = previous;
+
+            Ok(())
+        }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn milestones_survive_reopening_the_store() {
+        let path = std::env::temp_dir().join(format!("milestones-{}.sqlite", Pubkey::new_unique()));
+        let multisig_pda = Pubkey::new_unique();
+        let milestone = Milestone::new("1".to_string(), 100, Pubkey::new_unique()).unwrap();
+        let recipient = milestone.recipient;
+
+        let store = MilestoneStore::new(Store::open(&path).unwrap());
+        store.add_milestone(&multisig_pda, milestone.clone()).await.unwrap();
+        assert!(matches!(store.add_milestone(&multisig_pda, milestone).await, Err(MilestoneError::DuplicateMilestone(_))));
+        store.update_milestone(&multisig_pda, "1", move |milestone| milestone.propose(&recipient, 100, 3)).await.unwrap();
+        drop(store);
+
+        let reopened = MilestoneStore::new(Store::open(&path).unwrap());
+        let milestones = reopened.get_milestones(&multisig_pda).await.unwrap();
+        assert_eq!(1, milestones.len());
+        assert_eq!(Some(3), milestones[0].transaction_index);
+        assert!(reopened.get_milestones(&Pubkey::new_unique()).await.unwrap().is_empty());
+        drop(reopened);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reserved_milestone_is_withdrawn_once() {
+        let multisig_pda = Pubkey::new_unique();
+        let milestone = Milestone::new("1".to_string(), 100, Pubkey::new_unique()).unwrap();
+        let recipient = milestone.recipient;
+
+        let store = MilestoneStore::new(Store::open_in_memory().unwrap());
+        store.add_milestone(&multisig_pda, milestone.clone()).await.unwrap();
+
+        let previous = store.reserve_milestone(&multisig_pda, "1", move |milestone| milestone.propose(&recipient, 100, 3)).await.unwrap();
+        assert_eq!(milestone, previous);
+        assert!(matches!(
+            store.reserve_milestone(&multisig_pda, "1", move |milestone| milestone.propose(&recipient, 100, 4)).await,
+            Err(MilestoneError::AlreadyProposed { transaction_index: 3, .. })
+        ));
+
+        // The proposal did not land, the milestone can be proposed again
+        store.restore_milestone(&multisig_pda, previous).await.unwrap();
+        assert_eq!(vec![milestone], store.get_milestones(&multisig_pda).await.unwrap());
+        store.reserve_milestone(&multisig_pda, "1", move |milestone| milestone.propose(&recipient, 100, 4)).await.unwrap();
+    }
+}
@@ -1 +1,16 @@
+pub mod audit_service;
+pub mod balance_service;
+pub mod compute_budget_service;
+pub mod confirmation_service;
 pub mod dao_service;
+pub mod dry_run_service;
+pub mod error;
+pub mod indexer_service;
+pub mod instruction_decoder;
+pub mod milestone_service;
+pub mod nonce_service;
+pub mod preflight_service;
+pub mod refund_service;
+pub mod rent_service;
+pub mod sender_service;
+pub mod store_service;
\ No newline at end of file
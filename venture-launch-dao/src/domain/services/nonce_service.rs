@@ -0,0 +1,167 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::nonce_utils::nonblocking::data_from_account;
+use solana_sdk::hash::{hashv, Hash};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::nonce::State as NonceState;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction;
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+
+use super::error::NonceError;
+
+/// Seed of the nonce account the operator keeps for `member` of `multisig_pda`.
+/// Every member gets its own account, so an unsigned vote of one member is never invalidated by another
+pub fn get_nonce_account_seed(multisig_pda: &Pubkey, member: &Pubkey) -> String {
+    let hash = hashv(&[b"nonce", multisig_pda.as_ref(), member.as_ref()]);
+
+    // `create_with_seed` accepts at most 32 bytes of seed
+    hash.as_ref()[..16].iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn get_nonce_account_address(authority: &Pubkey, multisig_pda: &Pubkey, member: &Pubkey) -> Pubkey {
+    let seed = get_nonce_account_seed(multisig_pda, member);
+
+    Pubkey::create_with_seed(authority, &seed, &system_program::id()).expect("seed is 32 hex chars")
+}
+
+/// Creates the nonce account of `member` funded by and under the authority of the operator
+pub async fn instructions_create_nonce_account(
+    rpc_client: &RpcClient,
+    authority: &Pubkey,
+    multisig_pda: &Pubkey,
+    member: &Pubkey
+) -> Result<Vec<Instruction>, NonceError> {
+    let seed = get_nonce_account_seed(multisig_pda, member);
+    let nonce_account = get_nonce_account_address(authority, multisig_pda, member);
+    let lamports = rpc_client
+        .get_minimum_balance_for_rent_exemption(NonceState::size())
+        .await
+        .map_err(|_| NonceError::FailedToFetchRent)?;
+
+    Ok(system_instruction::create_nonce_account_with_seed(
+        authority,
+        &nonce_account,
+        authority,
+        &seed,
+        authority,
+        lamports
+    ))
+}
+
+/// Stored nonce of `nonce_account`, `None` when the account does not exist yet
+pub async fn get_nonce(rpc_client: &RpcClient, nonce_account: &Pubkey, authority: &Pubkey) -> Result<Option<Hash>, NonceError> {
+    let account = rpc_client
+        .get_account_with_commitment(nonce_account, rpc_client.commitment())
+        .await
+        .map_err(|_| NonceError::FailedToFetchNonceAccount(*nonce_account))?
+        .value;
+
+    let account = match account {
+        Some(account) => account,
+        None => return Ok(None)
+    };
+
+    let data = data_from_account(&account).map_err(|_| NonceError::InvalidNonceAccount(*nonce_account))?;
+    if data.authority != *authority {
+        return Err(NonceError::WrongNonceAuthority { nonce_account: *nonce_account, authority: data.authority });
+    }
+
+    Ok(Some(data.blockhash()))
+}
+
+/// Advancing the nonce invalidates every transaction built on the current one
+pub fn instruction_rotate_nonce_account(nonce_account: &Pubkey, authority: &Pubkey) -> Instruction {
+    system_instruction::advance_nonce_account(nonce_account, authority)
+}
+
+/// Withdraws everything from `nonce_account` back to the operator, which closes it
+pub async fn instruction_close_nonce_account(rpc_client: &RpcClient, nonce_account: &Pubkey, authority: &Pubkey) -> Result<Instruction, NonceError> {
+    let lamports = rpc_client
+        .get_balance(nonce_account)
+        .await
+        .map_err(|_| NonceError::FailedToFetchNonceAccount(*nonce_account))?;
+
+    Ok(system_instruction::withdraw_nonce_account(nonce_account, authority, authority, lamports))
+}
+
+/// Unsigned transaction paid by `payer` that stays valid until `nonce` is advanced.
+/// `advance_nonce_account` goes first, so the operator has to sign it as the nonce authority
+pub fn get_nonce_transaction(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    nonce_account: &Pubkey,
+    authority: &Pubkey,
+    nonce: Hash
+) -> Transaction {
+    let mut message = Message::new_with_nonce(instructions.to_vec(), Some(payer), nonce_account, authority);
+    message.recent_blockhash = nonce;
+
+    Transaction::new_unsigned(message)
+}
+
+/// Checks that a signed transaction coming back from a wallet advances `nonce_account` first
+pub fn check_nonce_transaction(tx: &Transaction, nonce_account: &Pubkey) -> Result<(), NonceError> {
+    let advance_nonce = system_instruction::advance_nonce_account(nonce_account, &Pubkey::default());
+
+    let first = tx
+        .message
+        .instructions
+        .first()
+        .ok_or(NonceError::NotANonceTransaction)?;
+    let program_id = tx.message.account_keys.get(first.program_id_index as usize);
+    let account = first
+        .accounts
+        .first()
+        .and_then(|index| tx.message.account_keys.get(*index as usize));
+
+    if program_id != Some(&system_program::id()) || first.data != advance_nonce.data || account != Some(nonce_account) {
+        return Err(NonceError::NotANonceTransaction);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{signature::Keypair, signer::Signer};
+
+    #[test]
+    fn nonce_account_is_unique_per_member() {
+        let authority = Pubkey::new_unique();
+        let multisig_pda = Pubkey::new_unique();
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+
+        assert_eq!(32, get_nonce_account_seed(&multisig_pda, &first).len());
+        assert_eq!(
+            get_nonce_account_address(&authority, &multisig_pda, &first),
+            get_nonce_account_address(&authority, &multisig_pda, &first)
+        );
+        assert_ne!(
+            get_nonce_account_address(&authority, &multisig_pda, &first),
+            get_nonce_account_address(&authority, &multisig_pda, &second)
+        );
+    }
+
+    #[test]
+    fn nonce_transaction_advances_nonce_first() {
+        let authority = Keypair::new();
+        let payer = Pubkey::new_unique();
+        let nonce_account = Pubkey::new_unique();
+        let nonce = Hash::new_unique();
+        let transfer = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+
+        let tx = get_nonce_transaction(std::slice::from_ref(&transfer), &payer, &nonce_account, &authority.pubkey(), nonce);
+
+        assert_eq!(nonce, tx.message.recent_blockhash);
+        assert_eq!(payer, tx.message.account_keys[0]);
+        assert!(check_nonce_transaction(&tx, &nonce_account).is_ok());
+        assert!(check_nonce_transaction(&tx, &Pubkey::new_unique()).is_err());
+
+        let plain = Transaction::new_with_payer(&[transfer], Some(&payer));
+        assert!(check_nonce_transaction(&plain, &nonce_account).is_err());
+    }
+}
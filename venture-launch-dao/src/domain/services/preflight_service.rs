@@ -0,0 +1,180 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use squads_multisig::squads_multisig_program::Multisig;
+use squads_multisig::state::Permission;
+
+use crate::domain::entities::token::mint::{unpack_token_account, MintInfo};
+
+use super::error::PreflightError;
+
+/// What the simulation of a transaction told us about its costs
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub fee: u64,
+    pub rent: u64,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+/// Simulates a signed or unsigned transaction and checks that its fee payer can cover fees and rent
+pub async fn preflight_transaction(rpc_client: &RpcClient, tx: &Transaction) -> Result<PreflightReport, PreflightError> {
+    let payer = tx.message.account_keys[0];
+
+    let fee = rpc_client
+        .get_fee_for_message(&tx.message)
+        .await
+        .map_err(|_| PreflightError::FailedToFetchFee)?;
+    let balance = rpc_client
+        .get_balance(&payer)
+        .await
+        .map_err(|_| PreflightError::FailedToFetchBalance(payer))?;
+
+    if balance < fee {
+        return Err(PreflightError::InsufficientFeePayerBalance { payer, balance, required: fee, logs: vec![] });
+    }
+
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        accounts: Some(RpcSimulateTransactionAccountsConfig {
+            encoding: None,
+            addresses: vec![payer.to_string()],
+        }),
+        ..RpcSimulateTransactionConfig::default()
+    };
+
+    let simulation = rpc_client
+        .simulate_transaction_with_config(tx, config)
+        .await
+        .map_err(|err| PreflightError::FailedToSimulateTransaction(err.to_string()))?
+        .value;
+
+    let logs = simulation.logs.unwrap_or_default();
+
+    if let Some(err) = simulation.err {
+        return match err {
+            TransactionError::InsufficientFundsForFee => {
+                Err(PreflightError::InsufficientFeePayerBalance { payer, balance, required: fee, logs })
+            },
+            TransactionError::InsufficientFundsForRent { .. } => {
+                Err(PreflightError::InsufficientFundsForRent { payer, balance, logs })
+            },
+            err => Err(PreflightError::SimulationFailed { err: err.to_string(), logs })
+        };
+    }
+
+    // Whatever the payer loses in the simulation apart from the fee goes to rent of new accounts
+    let post_balance = simulation
+        .accounts
+        .and_then(|accounts| accounts.into_iter().next().flatten())
+        .map(|account| account.lamports)
+        .unwrap_or(balance);
+    let rent = balance.saturating_sub(post_balance).saturating_sub(fee);
+
+    if balance < fee + rent {
+        return Err(PreflightError::InsufficientFeePayerBalance { payer, balance, required: fee + rent, logs });
+    }
+
+    Ok(PreflightReport {
+        fee,
+        rent,
+        units_consumed: simulation.units_consumed,
+        logs,
+    })
+}
+
+/// Checks that `member` is in the multisig and holds `permission`
+pub fn check_member_permission(multisig: &Multisig, member: &Pubkey, permission: Permission) -> Result<(), PreflightError> {
+    let member_config = match multisig.members.iter().find(|m| m.key == *member) {
+        Some(member_config) => member_config,
+        None => return Err(PreflightError::NotAMember { member: *member })
+    };
+
+    if !member_config.permissions.has(permission) {
+        return Err(PreflightError::MissingPermission { member: *member, permission: permission_name(permission) });
+    }
+
+    Ok(())
+}
+
+/// Checks that the vault's associated token account for the mint holds at least `amount`
+pub async fn check_vault_token_balance(rpc_client: &RpcClient, vault_pda: &Pubkey, mint_info: &MintInfo, amount: u64) -> Result<(), PreflightError> {
+    let vault_token_account = mint_info.get_token_account(vault_pda);
+
+    let account = rpc_client
+        .get_account(&vault_token_account)
+        .await
+        .map_err(|_| PreflightError::FailedToFetchTokenAccount(vault_token_account))?;
+    let balance = unpack_token_account(&vault_token_account, &account)
+        .map_err(|_| PreflightError::FailedToFetchTokenAccount(vault_token_account))?
+        .amount;
+
+    if balance < amount {
+        return Err(PreflightError::InsufficientVaultBalance { account: vault_token_account, balance, required: amount });
+    }
+
+    Ok(())
+}
+
+fn permission_name(permission: Permission) -> &'static str {
+    match permission {
+        Permission::Initiate => "Initiate",
+        Permission::Vote => "Vote",
+        Permission::Execute => "Execute",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use squads_multisig::state::{Member, Permissions};
+
+    fn multisig_with(members: Vec<Member>) -> Multisig {
+        Multisig {
+            create_key: Pubkey::new_unique(),
+            config_authority: Pubkey::default(),
+            threshold: 1,
+            time_lock: 0,
+            transaction_index: 0,
+            stale_transaction_index: 0,
+            rent_collector: None,
+            bump: 0,
+            members,
+        }
+    }
+
+    #[test]
+    fn member_with_permission_passes() {
+        let voter = Pubkey::new_unique();
+        let multisig = multisig_with(vec![Member { key: voter, permissions: Permissions::from_vec(&[Permission::Vote]) }]);
+
+        assert!(check_member_permission(&multisig, &voter, Permission::Vote).is_ok());
+    }
+
+    #[test]
+    fn missing_permission_and_unknown_member_fail() {
+        let voter = Pubkey::new_unique();
+        let multisig = multisig_with(vec![Member { key: voter, permissions: Permissions::from_vec(&[Permission::Vote]) }]);
+
+        assert!(matches!(
+            check_member_permission(&multisig, &voter, Permission::Execute),
+            Err(PreflightError::MissingPermission { permission: "Execute", .. })
+        ));
+        assert!(matches!(
+            check_member_permission(&multisig, &Pubkey::new_unique(), Permission::Vote),
+            Err(PreflightError::NotAMember { .. })
+        ));
+    }
+
+    #[test]
+    fn response_carries_simulation_logs() {
+        let err = PreflightError::SimulationFailed {
+            err: "custom program error: 0x1".to_string(),
+            logs: vec!["Program log: insufficient funds".to_string()],
+        };
+
+        assert!(err.to_response().contains("\"Program log: insufficient funds\""));
+    }
+}
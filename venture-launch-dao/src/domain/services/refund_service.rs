@@ -0,0 +1,182 @@
+use std::collections::HashSet;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use spl_token::state::Account as TokenAccount;
+
+use crate::domain::entities::token::mint::{unpack_token_account, MintInfo};
+
+use super::error::RefundError;
+
+/// Investors handled by one vault transaction. Executing it has to list the owner and token account
+/// of every investor, more than this does not fit in a single Solana transaction
+pub const REFUND_INVESTORS_PER_TRANSACTION: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefundShare {
+    pub investor: Pubkey,
+    pub weight: u64,
+    pub amount: u64,
+}
+
+/// Splits `total` between `weights` proportionally. Every share is rounded down and the units left over
+/// go one each to the largest remainders, ties broken by the lower pubkey, so the same input always
+/// gives the same amounts and they add up to `total` exactly
+pub fn split_pro_rata(total: u64, weights: &[(Pubkey, u64)]) -> Result<Vec<RefundShare>, RefundError> {
+    if weights.is_empty() {
+        return Err(RefundError::NoInvestors);
+    }
+
+    let mut seen = HashSet::with_capacity(weights.len());
+    for (investor, _) in weights {
+        if !seen.insert(*investor) {
+            return Err(RefundError::DuplicateInvestor(*investor));
+        }
+    }
+
+    let total_weight: u128 = weights.iter().map(|(_, weight)| *weight as u128).sum();
+    if total_weight == 0 {
+        return Err(RefundError::ZeroTotalWeight);
+    }
+
+    let mut shares: Vec<(RefundShare, u128)> = weights
+        .iter()
+        .map(|(investor, weight)| {
+            let scaled = total as u128 * *weight as u128;
+            let share = RefundShare {
+                investor: *investor,
+                weight: *weight,
+                amount: (scaled / total_weight) as u64,
+            };
+
+            (share, scaled % total_weight)
+        })
+        .collect();
+
+    let distributed: u64 = shares.iter().map(|(share, _)| share.amount).sum();
+    let left_over = (total - distributed) as usize;
+
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|a, b| {
+        shares[*b].1
+            .cmp(&shares[*a].1)
+            .then_with(|| shares[*a].0.investor.cmp(&shares[*b].0.investor))
+    });
+    for index in order.into_iter().take(left_over) {
+        shares[index].0.amount += 1;
+    }
+
+    Ok(shares.into_iter().map(|(share, _)| share).collect())
+}
+
+/// Holders of the mint weighted by their balance, accounts of `excluded` owners are left out.
+/// Token accounts are read from the program owning the mint
+pub async fn get_snapshot_weights(rpc_client: &RpcClient, mint_info: &MintInfo, excluded: &[Pubkey]) -> Result<Vec<(Pubkey, u64)>, RefundError> {
+    let mint = &mint_info.mint;
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, mint.as_ref()))];
+    // Token-2022 accounts grow with the extensions of their mint, only legacy ones have a fixed size
+    if mint_info.token_program == spl_token::id() {
+        filters.push(RpcFilterType::DataSize(TokenAccount::LEN as u64));
+    }
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc_client
+        .get_program_accounts_with_config(&mint_info.token_program, config)
+        .await
+        .map_err(|_| RefundError::FailedToFetchSnapshot(*mint))?;
+
+    let mut weights: Vec<(Pubkey, u64)> = Vec::new();
+    for (address, account) in accounts {
+        let token_account = unpack_token_account(&address, &account).map_err(|_| RefundError::FailedToFetchSnapshot(*mint))?;
+        if token_account.amount == 0 || excluded.contains(&token_account.owner) {
+            continue;
+        }
+
+        // An owner may hold the mint in several accounts
+        match weights.iter_mut().find(|(owner, _)| *owner == token_account.owner) {
+            Some((_, weight)) => *weight += token_account.amount,
+            None => weights.push((token_account.owner, token_account.amount))
+        }
+    }
+
+    weights.sort_by_key(|(owner, _)| *owner);
+
+    Ok(weights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn investors(count: u8) -> Vec<Pubkey> {
+        let mut investors: Vec<Pubkey> = (0..count).map(|index| Pubkey::new_from_array([index + 1; 32])).collect();
+        investors.sort();
+        investors
+    }
+
+    #[test]
+    fn shares_add_up_to_total() {
+        let investors = investors(3);
+        let weights = vec![(investors[0], 1), (investors[1], 1), (investors[2], 1)];
+
+        let shares = split_pro_rata(100, &weights).unwrap();
+
+        assert_eq!(100, shares.iter().map(|share| share.amount).sum::<u64>());
+        // 33.33 each, the single unit left goes to the lowest pubkey
+        assert_eq!(vec![34, 33, 33], shares.iter().map(|share| share.amount).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn left_over_goes_to_largest_remainder() {
+        let investors = investors(3);
+        let weights = vec![(investors[0], 10), (investors[1], 25), (investors[2], 65)];
+
+        let shares = split_pro_rata(7, &weights).unwrap();
+
+        // 0.7, 1.75, 4.55 -> 0, 1, 4 and two units left for 0.75 and 0.7
+        assert_eq!(vec![1, 2, 4], shares.iter().map(|share| share.amount).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn split_does_not_depend_on_order() {
+        let investors = investors(4);
+        let weights = vec![(investors[0], 3), (investors[1], 3), (investors[2], 3), (investors[3], 3)];
+        let mut reversed = weights.clone();
+        reversed.reverse();
+
+        let mut shares = split_pro_rata(10, &weights).unwrap();
+        let mut reversed_shares = split_pro_rata(10, &reversed).unwrap();
+        shares.sort_by_key(|share| share.investor);
+        reversed_shares.sort_by_key(|share| share.investor);
+
+        assert_eq!(shares, reversed_shares);
+    }
+
+    #[test]
+    fn large_amounts_do_not_overflow() {
+        let investors = investors(2);
+        let weights = vec![(investors[0], u64::MAX), (investors[1], u64::MAX)];
+
+        let shares = split_pro_rata(u64::MAX, &weights).unwrap();
+
+        assert_eq!(u64::MAX, shares[0].amount + shares[1].amount);
+    }
+
+    #[test]
+    fn rejects_invalid_weights() {
+        let investors = investors(2);
+
+        assert!(matches!(split_pro_rata(10, &[]), Err(RefundError::NoInvestors)));
+        assert!(matches!(split_pro_rata(10, &[(investors[0], 0)]), Err(RefundError::ZeroTotalWeight)));
+        assert!(matches!(
+            split_pro_rata(10, &[(investors[0], 1), (investors[0], 2)]),
+            Err(RefundError::DuplicateInvestor(_))
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use squads_multisig::anchor_lang::{AccountDeserialize, Discriminator};
+use squads_multisig::pda::{get_proposal_pda, get_transaction_pda};
+use squads_multisig::squads_multisig_program::{self, state::{ConfigTransaction, VaultTransaction}, Multisig};
+use squads_multisig::state::{Proposal, ProposalStatus};
+
+use super::error::RentError;
+
+/// Transactions closed by one Solana transaction of the sweeper
+pub const CLOSES_PER_TRANSACTION: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionKind {
+    Config,
+    Vault,
+}
+
+impl TransactionKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Vault => "vault",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosableTransaction {
+    pub transaction_index: u64,
+    pub kind: TransactionKind,
+    /// Rent held by the transaction and its proposal
+    pub lamports: u64,
+}
+
+/// Mirrors the checks of `config_transaction_accounts_close` and `vault_transaction_accounts_close`.
+/// Finished proposals can always be closed, open ones only once a config change made them stale.
+/// A stale approved vault transaction can still be executed, so it is kept
+pub fn is_closable(kind: TransactionKind, status: Option<&ProposalStatus>, is_stale: bool) -> bool {
+    match status {
+        None => is_stale,
+        Some(ProposalStatus::Draft { .. }) | Some(ProposalStatus::Active { .. }) => is_stale,
+        Some(ProposalStatus::Approved { .. }) => kind == TransactionKind::Config && is_stale,
+        Some(ProposalStatus::Rejected { .. }) | Some(ProposalStatus::Executed { .. }) | Some(ProposalStatus::Cancelled { .. }) => true,
+        #[allow(deprecated)]
+        Some(ProposalStatus::Executing) => false,
+        Some(_) => false,
+    }
+}
+
+/// Every transaction of the multisig that still holds rent and may be closed now
+pub async fn get_closable_transactions(
+    rpc_client: &RpcClient,
+    multisig_pda: &Pubkey,
+    multisig: &Multisig
+) -> Result<Vec<ClosableTransaction>, RentError> {
+    let program_id = squads_multisig_program::ID;
+    let indexes: Vec<u64> = (1..=multisig.transaction_index).collect();
+
+    let mut closable = Vec::new();
+    // Transaction and proposal of an index are fetched together, 100 accounts is the rpc limit
+    for chunk in indexes.chunks(50) {
+        let mut addresses = Vec::with_capacity(chunk.len() * 2);
+        for transaction_index in chunk {
+            addresses.push(get_transaction_pda(multisig_pda, *transaction_index, Some(&program_id)).0);
+            addresses.push(get_proposal_pda(multisig_pda, *transaction_index, Some(&program_id)).0);
+        }
+
+        let accounts = rpc_client
+            .get_multiple_accounts(&addresses)
+            .await
+            .map_err(|_| RentError::FailedToFetchTransactions)?;
+
+        for (transaction_index, pair) in chunk.iter().zip(accounts.chunks(2)) {
+            let (transaction, proposal) = (&pair[0], &pair[1]);
+
+            // Closed already
+            let Some(transaction) = transaction else { continue };
+            let Some(kind) = get_transaction_kind(transaction) else { continue };
+
+            let status = match proposal {
+                Some(proposal) => Some(
+                    Proposal::try_deserialize(&mut proposal.data.as_slice())
+                        .map_err(|_| RentError::FailedToDeserializeProposal(*transaction_index))?
+                        .status
+                ),
+                None => None
+            };
+
+            let is_stale = *transaction_index <= multisig.stale_transaction_index;
+            if is_closable(kind, status.as_ref(), is_stale) {
+                closable.push(ClosableTransaction {
+                    transaction_index: *transaction_index,
+                    kind,
+                    lamports: transaction.lamports + proposal.as_ref().map(|proposal| proposal.lamports).unwrap_or(0),
+                });
+            }
+        }
+    }
+
+    Ok(closable)
+}
+
+fn get_transaction_kind(account: &Account) -> Option<TransactionKind> {
+    let discriminator = account.data.get(..8)?;
+
+    if discriminator == ConfigTransaction::DISCRIMINATOR {
+        Some(TransactionKind::Config)
+    } else if discriminator == VaultTransaction::DISCRIMINATOR {
+        Some(TransactionKind::Vault)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finished_proposals_are_closable() {
+        for status in [
+            ProposalStatus::Executed { timestamp: 0 },
+            ProposalStatus::Rejected { timestamp: 0 },
+            ProposalStatus::Cancelled { timestamp: 0 },
+        ] {
+            assert!(is_closable(TransactionKind::Config, Some(&status), false));
+            assert!(is_closable(TransactionKind::Vault, Some(&status), false));
+        }
+    }
+
+    #[test]
+    fn open_proposals_are_closable_once_stale() {
+        let active = ProposalStatus::Active { timestamp: 0 };
+
+        assert!(!is_closable(TransactionKind::Vault, Some(&active), false));
+        assert!(is_closable(TransactionKind::Vault, Some(&active), true));
+        assert!(!is_closable(TransactionKind::Config, None, false));
+        assert!(is_closable(TransactionKind::Config, None, true));
+    }
+
+    #[test]
+    fn approved_vault_transaction_is_kept() {
+        let approved = ProposalStatus::Approved { timestamp: 0 };
+
+        assert!(!is_closable(TransactionKind::Vault, Some(&approved), true));
+        assert!(!is_closable(TransactionKind::Config, Some(&approved), false));
+        assert!(is_closable(TransactionKind::Config, Some(&approved), true));
+    }
+}
@@ -0,0 +1,293 @@
+use std::future::Future;
+use std::time::Duration;
+
+use dotenv::dotenv;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::TransactionStatus;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::confirmation_service::{get_confirmation_tracker, ConfirmationConfig};
+use super::error::SendError;
+
+pub const DEFAULT_RESEND_INTERVAL: Duration = Duration::from_millis(2_000);
+pub const DEFAULT_MAX_BLOCKHASH_RETRIES: u8 = 3;
+
+/// What happened to an attempt sent while handling the current request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Submission {
+    Broadcast(Signature),
+    /// The blockhash of the attempt expired, it is never going to be processed
+    Expired(Signature),
+}
+
+tokio::task_local! {
+    static SUBMITTED: UnboundedSender<Submission>;
+}
+
+/// Runs `future` reporting every attempt it broadcasts to `submitted`, so the caller learns about
+/// a transaction before it is confirmed
+pub async fn with_submitted<F: Future>(submitted: UnboundedSender<Submission>, future: F) -> F::Output {
+    SUBMITTED.scope(submitted, future).await
+}
+
+pub fn notify_submitted(submission: Submission) {
+    let _ = SUBMITTED.try_with(|submitted| submitted.send(submission));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SenderConfig {
+    /// How often the transaction is rebroadcast while its blockhash is valid
+    pub resend_interval: Duration,
+    /// How many times an expired transaction is signed again with a fresh blockhash
+    pub max_blockhash_retries: u8,
+}
+
+impl Default for SenderConfig {
+    fn default() -> Self {
+        Self {
+            resend_interval: DEFAULT_RESEND_INTERVAL,
+            max_blockhash_retries: DEFAULT_MAX_BLOCKHASH_RETRIES,
+        }
+    }
+}
+
+impl SenderConfig {
+    /// Reads `SEND_RESEND_INTERVAL_MS` and `SEND_MAX_BLOCKHASH_RETRIES`, falling back to the defaults
+    pub fn from_env() -> Result<Self, SendError> {
+        dotenv().ok();
+
+        let default = Self::default();
+
+        let resend_interval = match std::env::var("SEND_RESEND_INTERVAL_MS") {
+            Ok(value) => Duration::from_millis(value.parse::<u64>().map_err(|_| SendError::InvalidSenderConfig(value))?),
+            Err(_) => default.resend_interval
+        };
+
+        let max_blockhash_retries = match std::env::var("SEND_MAX_BLOCKHASH_RETRIES") {
+            Ok(value) => value.parse::<u8>().map_err(|_| SendError::InvalidSenderConfig(value))?,
+            Err(_) => default.max_blockhash_retries
+        };
+
+        Ok(Self { resend_interval, max_blockhash_retries })
+    }
+}
+
+/// Where a sent transaction ended up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendStatus {
+    Landed { slot: Slot },
+    Failed { slot: Slot, err: String },
+    /// The blockhash of every attempt expired without the transaction being processed,
+    /// `last_error` is the last error the RPC node answered a broadcast with
+    Expired { last_error: Option<String> },
+}
+
+impl SendStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Landed { .. } => "landed",
+            Self::Failed { .. } => "failed",
+            Self::Expired { .. } => "expired",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SentTransaction {
+    /// Signature of the last attempt, the only one that may still be processed
+    pub signature: Signature,
+    pub status: SendStatus,
+    pub attempts: u8,
+}
+
+/// State of one attempt after a poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Progress {
+    Pending,
+    Done(SendStatus),
+    /// Finalized block height passed `last_valid_block_height`, no fork can process the attempt anymore
+    BlockhashExpired,
+}
+
+fn progress(
+    status: Option<&TransactionStatus>,
+    commitment: CommitmentConfig,
+    finalized_block_height: u64,
+    last_valid_block_height: u64
+) -> Progress {
+    match status {
+        Some(status) => match &status.err {
+            Some(err) => Progress::Done(SendStatus::Failed { slot: status.slot, err: err.to_string() }),
+            None if status.satisfies_commitment(commitment) => Progress::Done(SendStatus::Landed { slot: status.slot }),
+            None => Progress::Pending
+        },
+        None if finalized_block_height > last_valid_block_height => Progress::BlockhashExpired,
+        None => Progress::Pending
+    }
+}
+
+/// Signs `tx` and broadcasts it every `resend_interval` until it reaches the client commitment
+/// or its blockhash expires. The wait between broadcasts ends early once the confirmation tracker
+/// sees the transaction processed. Only an attempt whose blockhash expired on the finalized chain
+/// without a trace in the signature history is signed again, so at most one attempt can ever
+/// execute and a config transaction is never executed twice
+pub async fn send_transaction(
+    rpc_client: &RpcClient,
+    config: &SenderConfig,
+    tx: &mut Transaction,
+    signers: &[&Keypair]
+) -> Result<SentTransaction, SendError> {
+    let commitment = rpc_client.commitment();
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: true,
+        max_retries: Some(0),
+        ..RpcSendTransactionConfig::default()
+    };
+    let tracker = get_confirmation_tracker();
+    let wait = ConfirmationConfig {
+        commitment,
+        timeout: config.resend_interval,
+        poll_interval: config.resend_interval,
+    };
+
+    let mut attempts = 0;
+    let mut last_error = None;
+    loop {
+        attempts += 1;
+
+        let (recent_blockhash, last_valid_block_height) = rpc_client
+            .get_latest_blockhash_with_commitment(commitment)
+            .await
+            .map_err(|err| SendError::FailedToFetchBlockhash(err.to_string()))?;
+        tx.try_sign(signers, recent_blockhash).map_err(|err| SendError::FailedToSign(err.to_string()))?;
+        let signature = tx.signatures[0];
+        let mut is_broadcast = false;
+
+        loop {
+            // A rebroadcast of an already processed transaction is rejected, the status poll below sees the outcome
+            match rpc_client.send_transaction_with_config(tx, send_config).await {
+                Ok(_) if !is_broadcast => {
+                    is_broadcast = true;
+                    notify_submitted(Submission::Broadcast(signature));
+                },
+                Ok(_) => {},
+                Err(err) => last_error = Some(err.to_string())
+            }
+
+            // Returns as soon as the signature is processed instead of sleeping out the interval,
+            // the status poll below stays the one that decides
+            tracker
+                .wait_for_confirmation(rpc_client, &signature, &wait)
+                .await
+                .map_err(|err| SendError::FailedToFetchStatus(err.to_string()))?;
+
+            let status = rpc_client
+                .get_signature_statuses(&[signature])
+                .await
+                .map_err(|err| SendError::FailedToFetchStatus(err.to_string()))?
+                .value
+                .into_iter()
+                .next()
+                .flatten();
+            let finalized_block_height = rpc_client
+                .get_block_height_with_commitment(CommitmentConfig::finalized())
+                .await
+                .map_err(|err| SendError::FailedToFetchStatus(err.to_string()))?;
+
+            match progress(status.as_ref(), commitment, finalized_block_height, last_valid_block_height) {
+                Progress::Pending => continue,
+                Progress::Done(status) => return Ok(SentTransaction { signature, status, attempts }),
+                Progress::BlockhashExpired => break,
+            }
+        }
+
+        // The recent status cache may have been purged, check the ledger before giving up on the attempt
+        let status = rpc_client
+            .get_signature_statuses_with_history(&[signature])
+            .await
+            .map_err(|err| SendError::FailedToFetchStatus(err.to_string()))?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+        if let Some(status) = status {
+            let status = match status.err {
+                Some(err) => SendStatus::Failed { slot: status.slot, err: err.to_string() },
+                None => SendStatus::Landed { slot: status.slot }
+            };
+            return Ok(SentTransaction { signature, status, attempts });
+        }
+
+        if attempts > config.max_blockhash_retries {
+            return Ok(SentTransaction { signature, status: SendStatus::Expired { last_error }, attempts });
+        }
+
+        notify_submitted(Submission::Expired(signature));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::transaction::TransactionError;
+    use solana_transaction_status::TransactionConfirmationStatus;
+
+    fn status(confirmations: Option<usize>, err: Option<TransactionError>) -> TransactionStatus {
+        TransactionStatus {
+            slot: 42,
+            confirmations,
+            status: match &err {
+                Some(err) => Err(err.clone()),
+                None => Ok(())
+            },
+            err,
+            confirmation_status: Some(match confirmations {
+                None => TransactionConfirmationStatus::Finalized,
+                Some(_) => TransactionConfirmationStatus::Confirmed,
+            }),
+        }
+    }
+
+    #[test]
+    fn unknown_signature_is_pending_until_blockhash_expires() {
+        assert_eq!(Progress::Pending, progress(None, CommitmentConfig::confirmed(), 100, 100));
+        assert_eq!(Progress::BlockhashExpired, progress(None, CommitmentConfig::confirmed(), 101, 100));
+    }
+
+    #[test]
+    fn landed_waits_for_commitment() {
+        let confirmed = status(Some(1), None);
+
+        assert_eq!(
+            Progress::Done(SendStatus::Landed { slot: 42 }),
+            progress(Some(&confirmed), CommitmentConfig::confirmed(), 0, 100)
+        );
+        assert_eq!(Progress::Pending, progress(Some(&confirmed), CommitmentConfig::finalized(), 0, 100));
+        assert_eq!(
+            Progress::Done(SendStatus::Landed { slot: 42 }),
+            progress(Some(&status(None, None)), CommitmentConfig::finalized(), 0, 100)
+        );
+    }
+
+    #[test]
+    fn processed_transaction_never_expires() {
+        let confirmed = status(Some(1), None);
+
+        assert_eq!(Progress::Pending, progress(Some(&confirmed), CommitmentConfig::finalized(), 200, 100));
+    }
+
+    #[test]
+    fn failed_transaction_is_reported_at_once() {
+        let failed = status(Some(1), Some(TransactionError::AccountNotFound));
+
+        match progress(Some(&failed), CommitmentConfig::finalized(), 0, 100) {
+            Progress::Done(SendStatus::Failed { slot, .. }) => assert_eq!(42, slot),
+            progress => panic!("unexpected {progress:?}"),
+        }
+    }
+}
@@ -0,0 +1,605 @@
+use std::future::Future;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use dotenv::dotenv;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use tokio::sync::OnceCell;
+
+use super::error::StoreError;
+use crate::domain::entities::milestone::entity::{Milestone, MilestoneStatus};
+
+pub const DEFAULT_STORE_PATH: &str = "venture-launch-dao.sqlite";
+
+/// Schema changes in order, a migration is applied once and never edited afterwards.
+/// `PRAGMA user_version` holds how many of them the database has seen
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE requests (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        command TEXT NOT NULL,
+        correlation_id TEXT,
+        payload TEXT NOT NULL,
+        status TEXT NOT NULL,
+        response TEXT,
+        created_at INTEGER NOT NULL,
+        finished_at INTEGER
+    );
+    CREATE TABLE daos (
+        multisig_pda TEXT PRIMARY KEY,
+        create_key TEXT NOT NULL,
+        vault_pda TEXT NOT NULL,
+        creator TEXT NOT NULL,
+        threshold INTEGER NOT NULL,
+        request_id INTEGER REFERENCES requests(id),
+        created_at INTEGER NOT NULL
+    );
+    CREATE TABLE signatures (
+        signature TEXT PRIMARY KEY,
+        request_id INTEGER REFERENCES requests(id),
+        status TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );
+    CREATE TABLE proposals (
+        multisig_pda TEXT NOT NULL,
+        transaction_index INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        request_id INTEGER REFERENCES requests(id),
+        created_at INTEGER NOT NULL,
+        PRIMARY KEY (multisig_pda, transaction_index)
+    );
+    CREATE INDEX signatures_request_id ON signatures (request_id);",
+    "CREATE TABLE indexer_cursors (
+        multisig_pda TEXT PRIMARY KEY,
+        slot INTEGER NOT NULL,
+        snapshot TEXT NOT NULL,
+        updated_at INTEGER NOT NULL
+    );",
+    "CREATE TABLE milestones (
+        multisig_pda TEXT NOT NULL,
+        id TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        recipient TEXT NOT NULL,
+        status TEXT NOT NULL,
+        transaction_index INTEGER,
+        withdrawn INTEGER NOT NULL,
+        request_id INTEGER REFERENCES requests(id),
+        updated_request_id INTEGER REFERENCES requests(id),
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (multisig_pda, id)
+    );",
+];
+
+tokio::task_local! {
+    /// Request the current broker message was recorded as, records written while handling it point to it
+    static REQUEST_ID: i64;
+}
+
+/// Runs `future` with `request_id` as the request every record written inside it belongs to
+pub async fn with_request<F: Future>(request_id: i64, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+pub fn current_request_id() -> Option<i64> {
+    REQUEST_ID.try_with(|request_id| *request_id).ok()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaoRecord {
+    pub multisig_pda: Pubkey,
+    pub create_key: Pubkey,
+    pub vault_pda: Pubkey,
+    pub creator: Pubkey,
+    pub threshold: u16,
+    pub request_id: Option<i64>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestRecord {
+    pub id: i64,
+    pub command: String,
+    pub correlation_id: Option<String>,
+    pub payload: String,
+    /// `pending` until the request is handled, then `ok` or `error`
+    pub status: String,
+    pub response: Option<String>,
+    pub created_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalRecord {
+    pub multisig_pda: Pubkey,
+    pub transaction_index: u64,
+    /// `config` or `vault`
+    pub kind: String,
+    pub request_id: Option<i64>,
+    pub created_at: i64,
+}
+
+/// SQLite database keeping what the service did, the chain alone can not tell which request created what
+#[derive(Clone)]
+pub struct Store {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let connection = Connection::open(path).map_err(|err| StoreError::FailedToOpenStore(err.to_string()))?;
+
+        Self::from_connection(connection)
+    }
+
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        let connection = Connection::open_in_memory().map_err(|err| StoreError::FailedToOpenStore(err.to_string()))?;
+
+        Self::from_connection(connection)
+    }
+
+    fn from_connection(mut connection: Connection) -> Result<Self, StoreError> {
+        connection
+            .execute_batch("PRAGMA journal_mode = WAL; PRAGMA foreign_keys = ON;")
+            .map_err(|err| StoreError::FailedToOpenStore(err.to_string()))?;
+        migrate(&mut connection)?;
+
+        Ok(Self { connection: Arc::new(Mutex::new(connection)) })
+    }
+
+    /// Runs `query` on a blocking thread, SQLite calls must not stall the runtime
+    async fn call<T: Send + 'static>(
+        &self,
+        query: impl FnOnce(&Connection) -> rusqlite::Result<T> + Send + 'static
+    ) -> Result<T, StoreError> {
+        let connection = self.connection.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().map_err(|err| StoreError::QueryFailed(err.to_string()))?;
+
+            query(&connection).map_err(|err| StoreError::QueryFailed(err.to_string()))
+        })
+        .await
+        .map_err(|err| StoreError::QueryFailed(err.to_string()))?
+    }
+
+    pub async fn insert_request(&self, command: &str, correlation_id: Option<String>, payload: &str) -> Result<i64, StoreError> {
+        let (command, payload) = (command.to_string(), payload.to_string());
+
+        self.call(move |connection| {
+            connection.execute(
+                "INSERT INTO requests (command, correlation_id, payload, status, created_at) VALUES (?1, ?2, ?3, 'pending', ?4)",
+                params![command, correlation_id, payload, now()],
+            )?;
+
+            Ok(connection.last_insert_rowid())
+        }).await
+    }
+
+    pub async fn finish_request(&self, request_id: i64, result: &Result<String, String>) -> Result<(), StoreError> {
+        let (status, response) = match result {
+            Ok(response) => ("ok", response.clone()),
+            Err(response) => ("error", response.clone()),
+        };
+
+        self.call(move |connection| {
+            connection.execute(
+                "UPDATE requests SET status = ?1, response = ?2, finished_at = ?3 WHERE id = ?4",
+                params![status, response, now(), request_id],
+            )?;
+
+            Ok(())
+        }).await
+    }
+
+    pub async fn get_request(&self, request_id: i64) -> Result<Option<RequestRecord>, StoreError> {
+        self.call(move |connection| {
+            connection
+                .query_row(
+                    "SELECT id, command, correlation_id, payload, status, response, created_at, finished_at FROM requests WHERE id = ?1",
+                    params![request_id],
+                    |row| Ok(RequestRecord {
+                        id: row.get(0)?,
+                        command: row.get(1)?,
+                        correlation_id: row.get(2)?,
+                        payload: row.get(3)?,
+                        status: row.get(4)?,
+                        response: row.get(5)?,
+                        created_at: row.get(6)?,
+                        finished_at: row.get(7)?,
+                    }),
+                )
+                .optional()
+        }).await
+    }
+
+    pub async fn insert_dao(&self, dao: DaoRecord) -> Result<(), StoreError> {
+        self.call(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO daos (multisig_pda, create_key, vault_pda, creator, threshold, request_id, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    dao.multisig_pda.to_string(),
+                    dao.create_key.to_string(),
+                    dao.vault_pda.to_string(),
+                    dao.creator.to_string(),
+                    dao.threshold,
+                    dao.request_id,
+                    dao.created_at
+                ],
+            )?;
+
+            Ok(())
+        }).await
+    }
+
+    pub async fn get_daos(&self) -> Result<Vec<DaoRecord>, StoreError> {
+        self.call(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT multisig_pda, create_key, vault_pda, creator, threshold, request_id, created_at FROM daos ORDER BY created_at"
+            )?;
+            let daos = statement.query_map([], dao_from_row)?.collect();
+
+            daos
+        }).await
+    }
+
+    pub async fn insert_signature(&self, signature: &Signature, request_id: Option<i64>, status: &str) -> Result<(), StoreError> {
+        let (signature, status) = (signature.to_string(), status.to_string());
+
+        self.call(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO signatures (signature, request_id, status, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![signature, request_id, status, now()],
+            )?;
+
+            Ok(())
+        }).await
+    }
+
+    pub async fn get_request_signatures(&self, request_id: i64) -> Result<Vec<String>, StoreError> {
+        self.call(move |connection| {
+            let mut statement = connection.prepare("SELECT signature FROM signatures WHERE request_id = ?1 ORDER BY created_at")?;
+            let signatures = statement.query_map(params![request_id], |row| row.get(0))?.collect();
+
+            signatures
+        }).await
+    }
+
+    pub async fn insert_proposal(&self, multisig_pda: &Pubkey, transaction_index: u64, kind: &str, request_id: Option<i64>) -> Result<(), StoreError> {
+        let (multisig_pda, kind) = (multisig_pda.to_string(), kind.to_string());
+
+        self.call(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO proposals (multisig_pda, transaction_index, kind, request_id, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![multisig_pda, transaction_index as i64, kind, request_id, now()],
+            )?;
+
+            Ok(())
+        }).await
+    }
+
+    pub async fn get_proposal(&self, multisig_pda: &Pubkey, transaction_index: u64) -> Result<Option<ProposalRecord>, StoreError> {
+        let multisig_pda = multisig_pda.to_string();
+
+        self.call(move |connection| {
+            connection
+                .query_row(
+                    "SELECT multisig_pda, transaction_index, kind, request_id, created_at FROM proposals
+                     WHERE multisig_pda = ?1 AND transaction_index = ?2",
+                    params![multisig_pda, transaction_index as i64],
+                    |row| Ok(ProposalRecord {
+                        multisig_pda: pubkey_from_row(row, 0)?,
+                        transaction_index: row.get::<_, i64>(1)? as u64,
+                        kind: row.get(2)?,
+                        request_id: row.get(3)?,
+                        created_at: row.get(4)?,
+                    }),
+                )
+                .optional()
+        }).await
+    }
+
+    /// Milestones of the DAO in the order they were created
+    pub async fn get_milestones(&self, multisig_pda: &Pubkey) -> Result<Vec<Milestone>, StoreError> {
+        let multisig_pda = multisig_pda.to_string();
+
+        self.call(move |connection| select_milestones(connection, &multisig_pda)).await
+    }
+
+    /// Runs `update` on the milestones of `multisig_pda` and writes back the ones it changed or added when it succeeds.
+    /// Reading and writing happen in one SQLite transaction on the locked connection, so concurrent updates never interleave.
+    /// `request_id` of a milestone is the request that created it, `updated_request_id` the one that changed it last,
+    /// `transaction_index` points to the row in `proposals` once the milestone is proposed
+    pub async fn update_milestones<T: Send + 'static, E: Send + 'static>(
+        &self,
+        multisig_pda: &Pubkey,
+        update: impl FnOnce(&mut Vec<Milestone>) -> Result<T, E> + Send + 'static
+    ) -> Result<Result<T, E>, StoreError> {
+        let multisig_pda = multisig_pda.to_string();
+        let request_id = current_request_id();
+
+        self.call(move |connection| {
+            let transaction = connection.unchecked_transaction()?;
+            let previous = select_milestones(&transaction, &multisig_pda)?;
+            let mut milestones = previous.clone();
+
+            let result = update(&mut milestones);
+            if result.is_ok() {
+                let now = now();
+                for milestone in milestones.iter().filter(|milestone| !previous.contains(milestone)) {
+                    transaction.execute(
+                        "INSERT INTO milestones
+                            (multisig_pda, id, amount, recipient, status, transaction_index, withdrawn, request_id, updated_request_id, created_at, updated_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8, ?9, ?9)
+                         ON CONFLICT (multisig_pda, id) DO UPDATE SET
+                            amount = excluded.amount,
+                            recipient = excluded.recipient,
+                            status = excluded.status,
+                            transaction_index = excluded.transaction_index,
+                            withdrawn = excluded.withdrawn,
+                            updated_request_id = excluded.updated_request_id,
+                            updated_at = excluded.updated_at",
+                        params![
+                            multisig_pda,
+                            milestone.id,
+                            milestone.amount as i64,
+                            milestone.recipient.to_string(),
+                            milestone.status.name(),
+                            milestone.transaction_index.map(|transaction_index| transaction_index as i64),
+                            milestone.withdrawn as i64,
+                            request_id,
+                            now
+                        ],
+                    )?;
+                }
+                transaction.commit()?;
+            }
+
+            Ok(result)
+        }).await
+    }
+
+    /// Last snapshot the indexer published every event for, as JSON
+    pub async fn get_indexer_cursor(&self, multisig_pda: &Pubkey) -> Result<Option<String>, StoreError> {
+        let multisig_pda = multisig_pda.to_string();
+
+        self.call(move |connection| {
+            connection
+                .query_row("SELECT snapshot FROM indexer_cursors WHERE multisig_pda = ?1", params![multisig_pda], |row| row.get(0))
+                .optional()
+        }).await
+    }
+
+    pub async fn set_indexer_cursor(&self, multisig_pda: &Pubkey, slot: u64, snapshot: String) -> Result<(), StoreError> {
+        let multisig_pda = multisig_pda.to_string();
+
+        self.call(move |connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO indexer_cursors (multisig_pda, slot, snapshot, updated_at) VALUES (?1, ?2, ?3, ?4)",
+                params![multisig_pda, slot as i64, snapshot, now()],
+            )?;
+
+            Ok(())
+        }).await
+    }
+}
+
+static STORE: OnceCell<Store> = OnceCell::const_new();
+
+/// Store at `STORE_PATH`, opened and migrated on first use
+pub async fn get_store() -> Result<&'static Store, StoreError> {
+    STORE
+        .get_or_try_init(|| async {
+            dotenv().ok();
+
+            let path = std::env::var("STORE_PATH").unwrap_or_else(|_| DEFAULT_STORE_PATH.into());
+
+            Store::open(path)
+        })
+        .await
+}
+
+/// Records a DAO created by the current request. The chain already has it, so a store failure is only logged
+pub async fn record_dao(multisig_pda: Pubkey, create_key: Pubkey, vault_pda: Pubkey, creator: Pubkey, threshold: u16) {
+    let dao = DaoRecord {
+        multisig_pda,
+        create_key,
+        vault_pda,
+        creator,
+        threshold,
+        request_id: current_request_id(),
+        created_at: now(),
+    };
+
+    match get_store().await {
+        Ok(store) => log_error(store.insert_dao(dao).await),
+        Err(err) => log_error::<()>(Err(err)),
+    }
+}
+
+/// Records a signature sent by the current request
+pub async fn record_signature(signature: &Signature, status: &str) {
+    match get_store().await {
+        Ok(store) => log_error(store.insert_signature(signature, current_request_id(), status).await),
+        Err(err) => log_error::<()>(Err(err)),
+    }
+}
+
+/// Records a squads transaction created by the current request, `kind` is `config` or `vault`
+pub async fn record_proposal(multisig_pda: &Pubkey, transaction_index: u64, kind: &str) {
+    match get_store().await {
+        Ok(store) => log_error(store.insert_proposal(multisig_pda, transaction_index, kind, current_request_id()).await),
+        Err(err) => log_error::<()>(Err(err)),
+    }
+}
+
+fn log_error<T>(result: Result<T, StoreError>) {
+    if let Err(err) = result {
+        eprintln!("store: {err}");
+    }
+}
+
+fn migrate(connection: &mut Connection) -> Result<(), StoreError> {
+    let version: usize = connection
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|err| StoreError::FailedToMigrate { version: 0, err: err.to_string() })?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let version = index + 1;
+        let to_error = |err: rusqlite::Error| StoreError::FailedToMigrate { version, err: err.to_string() };
+
+        let transaction = connection.transaction().map_err(to_error)?;
+        transaction.execute_batch(migration).map_err(to_error)?;
+        transaction.pragma_update(None, "user_version", version).map_err(to_error)?;
+        transaction.commit().map_err(to_error)?;
+    }
+
+    Ok(())
+}
+
+fn select_milestones(connection: &Connection, multisig_pda: &str) -> rusqlite::Result<Vec<Milestone>> {
+    let mut statement = connection.prepare(
+        "SELECT id, amount, recipient, status, transaction_index, withdrawn FROM milestones WHERE multisig_pda = ?1 ORDER BY rowid"
+    )?;
+    let milestones = statement.query_map(params![multisig_pda], milestone_from_row)?.collect();
+
+    milestones
+}
+
+fn milestone_from_row(row: &Row) -> rusqlite::Result<Milestone> {
+    let status: String = row.get(3)?;
+
+    Ok(Milestone {
+        id: row.get(0)?,
+        amount: row.get::<_, i64>(1)? as u64,
+        recipient: pubkey_from_row(row, 2)?,
+        status: MilestoneStatus::from_name(&status).ok_or_else(|| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, format!("unknown milestone status {status}").into())
+        })?,
+        transaction_index: row.get::<_, Option<i64>>(4)?.map(|transaction_index| transaction_index as u64),
+        withdrawn: row.get::<_, i64>(5)? as u64,
+    })
+}
+
+fn dao_from_row(row: &Row) -> rusqlite::Result<DaoRecord> {
+    Ok(DaoRecord {
+        multisig_pda: pubkey_from_row(row, 0)?,
+        create_key: pubkey_from_row(row, 1)?,
+        vault_pda: pubkey_from_row(row, 2)?,
+        creator: pubkey_from_row(row, 3)?,
+        threshold: row.get(4)?,
+        request_id: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn pubkey_from_row(row: &Row, index: usize) -> rusqlite::Result<Pubkey> {
+    let value: String = row.get(index)?;
+
+    value
+        .parse()
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(err)))
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_applied_once() {
+        let mut connection = Connection::open_in_memory().unwrap();
+
+        migrate(&mut connection).unwrap();
+        migrate(&mut connection).unwrap();
+
+        let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap();
+        assert_eq!(MIGRATIONS.len(), version);
+    }
+
+    #[tokio::test]
+    async fn records_point_to_their_request() {
+        let store = Store::open_in_memory().unwrap();
+        let multisig_pda = Pubkey::new_unique();
+
+        let request_id = store.insert_request("add_member", Some("42".to_string()), "{}").await.unwrap();
+        store.insert_proposal(&multisig_pda, 7, "config", Some(request_id)).await.unwrap();
+        store.insert_signature(&Signature::new_unique(), Some(request_id), "landed").await.unwrap();
+        store.finish_request(request_id, &Ok("\"status\": \"landed\"".to_string())).await.unwrap();
+
+        let proposal = store.get_proposal(&multisig_pda, 7).await.unwrap().unwrap();
+        assert_eq!(Some(request_id), proposal.request_id);
+        assert!(store.get_proposal(&multisig_pda, 8).await.unwrap().is_none());
+
+        let request = store.get_request(request_id).await.unwrap().unwrap();
+        assert_eq!("add_member", request.command);
+        assert_eq!("ok", request.status);
+        assert_eq!(1, store.get_request_signatures(request_id).await.unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn daos_are_listed_with_create_key() {
+        let store = Store::open_in_memory().unwrap();
+        let dao = DaoRecord {
+            multisig_pda: Pubkey::new_unique(),
+            create_key: Pubkey::new_unique(),
+            vault_pda: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            threshold: 1,
+            request_id: None,
+            created_at: now(),
+        };
+
+        store.insert_dao(dao.clone()).await.unwrap();
+
+        assert_eq!(vec![dao], store.get_daos().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn milestones_point_to_their_requests() {
+        let store = Store::open_in_memory().unwrap();
+        let multisig_pda = Pubkey::new_unique();
+        let milestone = Milestone::new("1".to_string(), 100, Pubkey::new_unique()).unwrap();
+        let recipient = milestone.recipient;
+
+        let created_by = store.insert_request("create_milestone", None, "{}").await.unwrap();
+        let added = milestone.clone();
+        with_request(created_by, store.update_milestones(&multisig_pda, move |milestones| {
+            milestones.push(added);
+            Ok::<_, ()>(())
+        })).await.unwrap().unwrap();
+
+        let proposed_by = store.insert_request("withdraw", None, "{}").await.unwrap();
+        with_request(proposed_by, store.update_milestones(&multisig_pda, move |milestones| milestones[0].propose(&recipient, 100, 7)))
+            .await
+            .unwrap()
+            .unwrap();
+        // A failed update leaves the milestones as they were
+        assert!(store.update_milestones(&multisig_pda, move |milestones| milestones[0].propose(&recipient, 100, 8)).await.unwrap().is_err());
+
+        let milestones = store.get_milestones(&multisig_pda).await.unwrap();
+        assert_eq!(1, milestones.len());
+        assert_eq!(MilestoneStatus::Proposed, milestones[0].status);
+        assert_eq!(Some(7), milestones[0].transaction_index);
+
+        let request_ids = store.call(move |connection| {
+            connection.query_row(
+                "SELECT request_id, updated_request_id FROM milestones WHERE multisig_pda = ?1",
+                params![multisig_pda.to_string()],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )
+        }).await.unwrap();
+        assert_eq!((created_by, proposed_by), request_ids);
+    }
+
+    #[tokio::test]
+    async fn records_take_request_from_scope() {
+        assert_eq!(None, current_request_id());
+        assert_eq!(Some(3), with_request(3, async { current_request_id() }).await);
+    }
+}
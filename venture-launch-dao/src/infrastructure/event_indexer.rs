@@ -0,0 +1,192 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dotenv::dotenv;
+use futures::stream::{select_all, StreamExt};
+use solana_client::nonblocking::pubsub_client::{PubsubClient, PubsubClientError};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account::get_associated_token_address;
+use squads_multisig::pda::get_vault_pda;
+use squads_multisig::squads_multisig_program;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::domain::services::confirmation_service::get_ws_url;
+use crate::domain::services::dao_service::{get_rpc_client, get_vault_token_mint};
+use crate::domain::services::error::IndexerError;
+use crate::domain::services::indexer_service::{diff_snapshots, get_dao_snapshot, DaoSnapshot};
+use crate::domain::services::store_service::{get_store, Store};
+use crate::infrastructure::rabbitMQ_utils::rabbitMQ_event_publisher::RabbitMQEventPublisher;
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(10_000);
+
+pub struct EventIndexerInitArgs {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+}
+
+/// Watches every DAO in the store and publishes what changed on chain as events on `broker.events`.
+/// Account subscriptions only wake the indexer up, every change is found by comparing the DAO with
+/// the cursor of the last published snapshot, so a missed notification is picked up by the next poll
+pub struct EventIndexer {
+    publisher: RabbitMQEventPublisher,
+    rpc_client: RpcClient,
+    ws_url: String,
+    poll_interval: Duration,
+    token_mint: Pubkey,
+}
+
+impl EventIndexer {
+    pub async fn new(args: EventIndexerInitArgs) -> Result<Self, String> {
+        dotenv().ok();
+
+        let publisher = RabbitMQEventPublisher::new(&args.host, args.port, &args.username, &args.password).await?;
+        let rpc_client = get_rpc_client()?;
+        let ws_url = get_ws_url(&rpc_client.url());
+
+        let poll_interval = match std::env::var("INDEXER_POLL_INTERVAL_MS") {
+            Ok(value) => Duration::from_millis(value.parse::<u64>().map_err(|_| format!("Invalid INDEXER_POLL_INTERVAL_MS {value}"))?),
+            Err(_) => DEFAULT_POLL_INTERVAL
+        };
+
+        Ok(Self {
+            publisher,
+            rpc_client,
+            ws_url,
+            poll_interval,
+            token_mint: get_vault_token_mint(),
+        })
+    }
+
+    pub async fn start_indexer(&self) -> Result<(), String> {
+        let store = get_store().await.map_err(|err| err.to_string())?;
+
+        let pubsub = match PubsubClient::new(&self.ws_url).await {
+            Ok(pubsub) => Some(Arc::new(pubsub)),
+            Err(err) => {
+                eprintln!("indexer: {} unavailable, polling only: {err}", self.ws_url);
+                None
+            }
+        };
+
+        let (wake, mut woken) = mpsc::unbounded_channel::<Pubkey>();
+        let mut watched: HashSet<Pubkey> = HashSet::new();
+        let mut interval = tokio::time::interval(self.poll_interval);
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    let daos = match store.get_daos().await {
+                        Ok(daos) => daos,
+                        Err(err) => {
+                            eprintln!("indexer: {err}");
+                            continue;
+                        }
+                    };
+
+                    for dao in daos {
+                        if let Some(pubsub) = &pubsub {
+                            if watched.insert(dao.multisig_pda) {
+                                tokio::spawn(watch_dao(pubsub.clone(), dao.multisig_pda, self.token_mint, wake.clone()));
+                            }
+                        }
+
+                        self.index_dao_logged(store, &dao.multisig_pda).await;
+                    }
+                },
+                Some(multisig_pda) = woken.recv() => {
+                    // A single transaction touches several watched accounts, index each DAO once
+                    let mut pending = HashSet::from([multisig_pda]);
+                    while let Ok(multisig_pda) = woken.try_recv() {
+                        pending.insert(multisig_pda);
+                    }
+
+                    for multisig_pda in pending {
+                        self.index_dao_logged(store, &multisig_pda).await;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn index_dao_logged(&self, store: &Store, multisig_pda: &Pubkey) {
+        if let Err(err) = self.index_dao(store, multisig_pda).await {
+            eprintln!("indexer {multisig_pda}: {err}");
+        }
+    }
+
+    /// Publishes the events since the cursor of `multisig_pda` and moves the cursor once all of them are confirmed.
+    /// A crash in between publishes them again, consumers drop duplicates by event id
+    async fn index_dao(&self, store: &Store, multisig_pda: &Pubkey) -> Result<(), IndexerError> {
+        let previous: Option<DaoSnapshot> = store
+            .get_indexer_cursor(multisig_pda)
+            .await
+            .map_err(|err| IndexerError::FailedToAccessCursor(err.to_string()))?
+            .and_then(|cursor| serde_json::from_str(&cursor).ok());
+
+        let current = get_dao_snapshot(&self.rpc_client, multisig_pda, &self.token_mint, previous.as_ref()).await?;
+
+        match &previous {
+            // A lagging rpc node must not move the cursor back
+            Some(previous) if current.slot < previous.slot => return Ok(()),
+            Some(previous) => {
+                for event in diff_snapshots(multisig_pda, &self.token_mint, previous, &current) {
+                    let message = serde_json::to_string(&event).map_err(|err| IndexerError::FailedToPublish(err.to_string()))?;
+                    self.publisher.publish_message(&message).await.map_err(IndexerError::FailedToPublish)?;
+                }
+            },
+            // The first snapshot of a DAO is the baseline, its history is not replayed
+            None => {}
+        }
+
+        let cursor = serde_json::to_string(&current).map_err(|err| IndexerError::FailedToAccessCursor(err.to_string()))?;
+        store
+            .set_indexer_cursor(multisig_pda, current.slot, cursor)
+            .await
+            .map_err(|err| IndexerError::FailedToAccessCursor(err.to_string()))
+    }
+}
+
+/// Wakes the indexer up whenever a proposal, transaction, the multisig or the vault of `multisig_pda` changes
+async fn watch_dao(pubsub: Arc<PubsubClient>, multisig_pda: Pubkey, token_mint: Pubkey, wake: UnboundedSender<Pubkey>) {
+    if let Err(err) = subscribe_dao(&pubsub, multisig_pda, token_mint, wake).await {
+        eprintln!("indexer {multisig_pda}: subscription failed, polling only: {err}");
+    }
+}
+
+async fn subscribe_dao(pubsub: &PubsubClient, multisig_pda: Pubkey, token_mint: Pubkey, wake: UnboundedSender<Pubkey>) -> Result<(), PubsubClientError> {
+    let program_id = squads_multisig_program::ID;
+    let (vault_pda, _) = get_vault_pda(&multisig_pda, 0, Some(&program_id));
+    let vault_token_account = get_associated_token_address(&vault_pda, &token_mint);
+
+    // Every squads account of the multisig keeps its key right after the anchor discriminator
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, multisig_pda.as_ref()))]),
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let (program_accounts, _unsubscribe_program) = pubsub.program_subscribe(&program_id, Some(config)).await?;
+    let (multisig, _unsubscribe_multisig) = pubsub.account_subscribe(&multisig_pda, None).await?;
+    let (vault, _unsubscribe_vault) = pubsub.account_subscribe(&vault_pda, None).await?;
+    let (vault_token, _unsubscribe_vault_token) = pubsub.account_subscribe(&vault_token_account, None).await?;
+
+    let mut changes = select_all(vec![
+        program_accounts.map(|_| ()).boxed(),
+        multisig.map(|_| ()).boxed(),
+        vault.map(|_| ()).boxed(),
+        vault_token.map(|_| ()).boxed(),
+    ]);
+
+    while changes.next().await.is_some() {
+        if wake.send(multisig_pda).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -1,2 +1,3 @@
 pub mod rabbitMQ_utils;
-pub mod request_handler;
\ No newline at end of file
+pub mod request_handler;
+pub mod event_indexer;
\ No newline at end of file
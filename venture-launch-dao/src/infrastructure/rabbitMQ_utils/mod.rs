@@ -1,4 +1,5 @@
 pub mod rabbitMQ_publisher;
+pub mod rabbitMQ_event_publisher;
 pub mod rabbitMQ_consumer;
 pub mod broker;
 pub mod error;
\ No newline at end of file
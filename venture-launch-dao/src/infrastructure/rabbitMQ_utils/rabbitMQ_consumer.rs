@@ -1,10 +1,37 @@
 use amqprs::{channel::{BasicAckArguments, Channel}, consumer::AsyncConsumer, BasicProperties, Deliver, FieldName, FieldValue};
 use async_trait::async_trait;
+use lazy_static::lazy_static;
 use serde::Deserialize;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use crate::domain::entities::memo::audit_memo::{with_audit_context, AuditContext};
+use crate::domain::services::confirmation_service::{get_confirmation_tracker, Confirmation, ConfirmationConfig};
+use crate::domain::services::dao_service::get_rpc_client;
+use crate::domain::services::sender_service::{with_submitted, Submission};
+use crate::domain::services::store_service::{get_store, with_request};
 use crate::infrastructure::rabbitMQ_utils::rabbitMQ_publisher::RabbitMQPublisher;
 use crate::infrastructure::request_handler::consumers::{add_member::{self, AddMemberDaoSchema}, change_threshold::{self, ChangeThresholdDaoSchema}, create_dao::{self, CreateDaoSchema}, execute_proposal::{self, ProposalExecuteDaoSchema}, remove_member::{self, RemoveMemberDaoSchema}, vote::{self, VoteDaoSchema}, withdraw::{self, WithdrawDaoSchema}};
+use crate::infrastructure::request_handler::consumers::{add_spending_limit::{self, AddSpendingLimitDaoSchema}, batch_config::{self, BatchConfigDaoSchema}, get_spending_limits::{self, GetSpendingLimitsDaoSchema}, remove_spending_limit::{self, RemoveSpendingLimitDaoSchema}, use_spending_limit::{self, UseSpendingLimitDaoSchema}};
+use crate::infrastructure::request_handler::consumers::{close_nonce_account::{self, CloseNonceAccountDaoSchema}, close_transaction_buffer::{self, CloseTransactionBufferDaoSchema}, prepare_vote::{self, PrepareVoteDaoSchema}, rotate_nonce_account::{self, RotateNonceAccountDaoSchema}, submit_signed_vote::{self, SubmitSignedVoteDaoSchema}};
+use crate::infrastructure::request_handler::consumers::refund::{self, RefundDaoSchema};
+use crate::infrastructure::request_handler::consumers::{create_milestone::{self, CreateMilestoneDaoSchema}, get_milestones::{self, GetMilestonesDaoSchema}};
+use crate::infrastructure::request_handler::consumers::reclaim_rent::{self, ReclaimRentDaoSchema};
+use crate::infrastructure::request_handler::consumers::{get_dao_balances::{self, GetDaoBalancesDaoSchema}, get_dao_history::{self, GetDaoHistoryDaoSchema}, get_daos::{self, GetDaosDaoSchema}, get_proposal::{self, GetProposalDaoSchema}};
+use crate::infrastructure::request_handler::consumers::{create_vault::{self, CreateVaultSchema}, deposit_to_vault::{self, DepositToVaultSchema}, get_vault_balance::{self, GetVaultBalanceSchema}, withdraw_from_vault::{self, WithdrawFromVaultSchema}};
+use crate::infrastructure::request_handler::consumers::{create_escrow::{self, CreateEscrowSchema}, withdraw_from_escrow::{self, WithdrawFromEscrowSchema}};
+use crate::infrastructure::request_handler::consumers::{fund_vault_with_wsol::{self, FundVaultWithWsolSchema}, unwrap_vault_wsol::{self, UnwrapVaultWsolSchema}};
+
+lazy_static! {
+    /// One lock per `multisig_pda`, every command of a DAO takes the next transaction index of its multisig
+    static ref MULTISIG_LOCKS: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>> = std::sync::Mutex::new(HashMap::new());
+}
+
+#[derive(Clone)]
 pub struct RabbitMQConsumer {
-    publisher: Option<RabbitMQPublisher>
+    publisher: Option<Arc<RabbitMQPublisher>>
 }
 
 impl RabbitMQConsumer {
@@ -22,7 +49,7 @@ impl RabbitMQConsumer {
         password: &str,) {
         let publisher = RabbitMQPublisher::new(host, port, username, password).await.unwrap();
 
-        self.publisher = Some(publisher);
+        self.publisher = Some(Arc::new(publisher));
     }
 
     fn load_schema<'a, T: Deserialize<'a>>(&self, raw_json: &'a str) -> Result<T, String> {
@@ -32,6 +59,100 @@ impl RabbitMQConsumer {
         };
     }
 
+    async fn publish(&self, message: String, correlation_id: &Option<String>) {
+        if let Some(publisher) = &self.publisher {
+            let _ = publisher.publish_message(&message, correlation_id.as_deref()).await;
+        }
+    }
+
+    /// Runs the command, recording it and everything it writes to the store as one request.
+    /// Commands of the same `multisig_pda` run one at a time, in the order they are handled.
+    /// The memos of the transactions it builds carry the request id and the platform ids of the payload
+    async fn handle(&self, command: &str, correlation_id: Option<String>, raw_json_schema: &str) -> Result<String, String> {
+        let request_id = match get_store().await {
+            Ok(store) => store.insert_request(command, correlation_id, raw_json_schema).await.map_err(|err| eprintln!("store: {err}")).ok(),
+            Err(err) => {
+                eprintln!("store: {err}");
+                None
+            }
+        };
+
+        // Held until the command is done, in async mode as well, so commands of one DAO never build for the same transaction index
+        let multisig_lock = get_multisig_lock(raw_json_schema);
+        let _multisig_guard = match &multisig_lock {
+            Some(multisig_lock) => Some(multisig_lock.lock().await),
+            None => None
+        };
+
+        let audit_context = AuditContext::from_request(request_id, raw_json_schema);
+        let run = with_audit_context(audit_context, self.run_consumer(command, raw_json_schema));
+        let result: Result<String, String> = match request_id {
+            Some(request_id) => with_request(request_id, run).await,
+            None => run.await
+        };
+
+        if let (Some(request_id), Ok(store)) = (request_id, get_store().await) {
+            if let Err(err) = store.finish_request(request_id, &result).await {
+                eprintln!("store: {err}");
+            }
+        }
+
+        result
+    }
+
+    /// Answers `submitted` with the signature as soon as the first transaction of the command is broadcast,
+    /// then `confirmed` with the usual response once the command is done and `finalized` once all its
+    /// transactions are, or `failed`. Every message carries the correlation id of the request.
+    /// A command failing before it sends anything, or sending nothing at all, is answered as in sync mode
+    async fn handle_async(&self, command: String, correlation_id: Option<String>, raw_json_schema: String) {
+        let (submitted, mut submissions) = mpsc::unbounded_channel();
+        let run = with_submitted(submitted, self.handle(&command, correlation_id.clone(), &raw_json_schema));
+        tokio::pin!(run);
+
+        let mut signatures: Vec<Signature> = Vec::new();
+        let mut is_submitted = false;
+        let result = loop {
+            tokio::select! {
+                result = &mut run => break result,
+                Some(submission) = submissions.recv() => {
+                    if let Submission::Broadcast(signature) = submission {
+                        if !is_submitted {
+                            is_submitted = true;
+                            self.publish(submission_message(&command, "submitted", &[signature], None), &correlation_id).await;
+                        }
+                    }
+                    apply_submission(&mut signatures, submission);
+                }
+            }
+        };
+        while let Ok(submission) = submissions.try_recv() {
+            apply_submission(&mut signatures, submission);
+        }
+
+        let success_message = match result {
+            Ok(success_message) if !is_submitted => {
+                self.publish(format!("{{\"command_type\" : \"{command}\", {success_message}}}"), &correlation_id).await;
+                return;
+            },
+            Ok(success_message) => success_message,
+            Err(error_message) => {
+                eprintln!("[{:?} RABBITMQ ERROR] {}", chrono::Utc::now(), error_message);
+                if is_submitted {
+                    self.publish(submission_message(&command, "failed", &signatures, Some(&error_message)), &correlation_id).await;
+                }
+                return;
+            }
+        };
+
+        self.publish(submission_message(&command, "confirmed", &signatures, Some(&success_message)), &correlation_id).await;
+        println!("[{:?} RABBITMQ INFO] {}", chrono::Utc::now(), success_message);
+
+        match wait_for_finalized(&signatures).await {
+            Ok(()) => self.publish(submission_message(&command, "finalized", &signatures, None), &correlation_id).await,
+            Err(error_message) => self.publish(submission_message(&command, "failed", &signatures, Some(&error_message)), &correlation_id).await
+        }
+    }
+
     async fn run_consumer(&self, consumer_name: &str, raw_json_schema: &str) -> Result<String, String> {
         return match consumer_name {
             "create_dao" => {
@@ -70,6 +191,136 @@ impl RabbitMQConsumer {
                 println!("{:?}",json);
                 execute_proposal::consume(json).await
             },
+            "batch_config" => {
+                let json: BatchConfigDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                batch_config::consume(json).await
+            },
+            "add_spending_limit" => {
+                let json: AddSpendingLimitDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                add_spending_limit::consume(json).await
+            },
+            "remove_spending_limit" => {
+                let json: RemoveSpendingLimitDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                remove_spending_limit::consume(json).await
+            },
+            "use_spending_limit" => {
+                let json: UseSpendingLimitDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                use_spending_limit::consume(json).await
+            },
+            "get_spending_limits" => {
+                let json: GetSpendingLimitsDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                get_spending_limits::consume(json).await
+            },
+            "prepare_vote" => {
+                let json: PrepareVoteDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                prepare_vote::consume(json).await
+            },
+            "submit_signed_vote" => {
+                let json: SubmitSignedVoteDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                submit_signed_vote::consume(json).await
+            },
+            "rotate_nonce_account" => {
+                let json: RotateNonceAccountDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                rotate_nonce_account::consume(json).await
+            },
+            "close_nonce_account" => {
+                let json: CloseNonceAccountDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                close_nonce_account::consume(json).await
+            },
+            "close_transaction_buffer" => {
+                let json: CloseTransactionBufferDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                close_transaction_buffer::consume(json).await
+            },
+            "refund" => {
+                let json: RefundDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                refund::consume(json).await
+            },
+            "create_milestone" => {
+                let json: CreateMilestoneDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                create_milestone::consume(json).await
+            },
+            "get_milestones" => {
+                let json: GetMilestonesDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                get_milestones::consume(json).await
+            },
+            "reclaim_rent" => {
+                let json: ReclaimRentDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                reclaim_rent::consume(json).await
+            },
+            "get_daos" => {
+                let json: GetDaosDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                get_daos::consume(json).await
+            },
+            "get_dao_balances" => {
+                let json: GetDaoBalancesDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                get_dao_balances::consume(json).await
+            },
+            "get_dao_history" => {
+                let json: GetDaoHistoryDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                get_dao_history::consume(json).await
+            },
+            "get_proposal" => {
+                let json: GetProposalDaoSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                get_proposal::consume(json).await
+            },
+            "create_vault" => {
+                let json: CreateVaultSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                create_vault::consume(json).await
+            },
+            "deposit_to_vault" => {
+                let json: DepositToVaultSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                deposit_to_vault::consume(json).await
+            },
+            "withdraw_from_vault" => {
+                let json: WithdrawFromVaultSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                withdraw_from_vault::consume(json).await
+            },
+            "get_vault_balance" => {
+                let json: GetVaultBalanceSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                get_vault_balance::consume(json).await
+            },
+            "create_escrow" => {
+                let json: CreateEscrowSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                create_escrow::consume(json).await
+            },
+            "withdraw_from_escrow" => {
+                let json: WithdrawFromEscrowSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                withdraw_from_escrow::consume(json).await
+            },
+            "fund_vault_with_wsol" => {
+                let json: FundVaultWithWsolSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                fund_vault_with_wsol::consume(json).await
+            },
+            "unwrap_vault_wsol" => {
+                let json: UnwrapVaultWsolSchema = self.load_schema(raw_json_schema)?;
+                println!("{:?}",json);
+                unwrap_vault_wsol::consume(json).await
+            },
             unknown_command => Err(format!("Unknown command: {}", unknown_command)),
         };
     }
@@ -121,13 +372,28 @@ impl AsyncConsumer for RabbitMQConsumer {
             }
         };
 
-        let result: Result<String, String> = self.run_consumer(&command, raw_string).await;
+        let mode_header_key: FieldName = "mode".try_into().unwrap();
+        let is_async = match headers.get(&mode_header_key) {
+            Some(FieldValue::S(mode)) => mode.to_string() == "async",
+            _ => false
+        };
+        let correlation_id = basic_properties.correlation_id().map(|correlation_id| correlation_id.to_string());
+        let raw_string = raw_string.to_string();
+
+        // In async mode the next delivery is handled while this one waits for its transactions,
+        // commands of the same DAO still wait for each other in `handle`
+        if is_async {
+            let consumer = self.clone();
+            tokio::spawn(async move {
+                consumer.handle_async(command, correlation_id, raw_string).await;
+            });
+            return;
+        }
 
+        let result = self.handle(&command, correlation_id.clone(), &raw_string).await;
         match result {
             Ok(success_message) => {
-                if let Some(publisher) = &self.publisher {
-                    let _ = publisher.publish_message(format!("{{\"command_type\" : \"{command}\", {success_message}}}").as_str()).await;
-                }
+                self.publish(format!("{{\"command_type\" : \"{command}\", {success_message}}}"), &correlation_id).await;
                 println!(
                     "[{:?} RABBITMQ INFO] {}",
                     chrono::Utc::now(),
@@ -144,3 +410,79 @@ impl AsyncConsumer for RabbitMQConsumer {
         }
     }
 }
+
+/// Lock of the DAO the payload targets, `None` for commands without a `multisig_pda`
+fn get_multisig_lock(raw_json_schema: &str) -> Option<Arc<Mutex<()>>> {
+    let payload: serde_json::Value = serde_json::from_str(raw_json_schema).ok()?;
+    let multisig_pda = payload.get("multisig_pda")?.as_str()?;
+
+    let mut locks = MULTISIG_LOCKS.lock().unwrap();
+    Some(locks.entry(multisig_pda.to_string()).or_default().clone())
+}
+
+/// Keeps the attempts that may still land, an expired attempt is replaced by a new one
+fn apply_submission(signatures: &mut Vec<Signature>, submission: Submission) {
+    match submission {
+        Submission::Broadcast(signature) => signatures.push(signature),
+        Submission::Expired(signature) => signatures.retain(|broadcast| *broadcast != signature)
+    }
+}
+
+/// Follow-up of an async request. The signatures are the ones broadcast so far, `fragment` is
+/// the response or the error of the command
+fn submission_message(command: &str, submission: &str, signatures: &[Signature], fragment: Option<&str>) -> String {
+    let signatures: Vec<String> = signatures.iter().map(|signature| format!("\"{signature}\"")).collect();
+    let mut message = format!(
+        "{{\"command_type\" : \"{command}\", \"submission\": \"{submission}\", \"submitted_signatures\": [{}]",
+        signatures.join(", ")
+    );
+    if let Some(fragment) = fragment {
+        message.push_str(", ");
+        message.push_str(fragment);
+    }
+    message.push('}');
+
+    message
+}
+
+async fn wait_for_finalized(signatures: &[Signature]) -> Result<(), String> {
+    let rpc_client = get_rpc_client()?;
+    let config = ConfirmationConfig::from_env()
+        .map(|config| ConfirmationConfig { commitment: CommitmentConfig::finalized(), ..config })
+        .map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+    let tracker = get_confirmation_tracker();
+    for signature in signatures {
+        let confirmation = tracker
+            .wait_for_confirmation(&rpc_client, signature, &config)
+            .await
+            .map_err(|err| format!("\"msg\": \"{err}\""))?;
+
+        let msg = match confirmation {
+            Confirmation::Confirmed { .. } => continue,
+            Confirmation::Failed { err, .. } => format!("Transaction {signature} failed: {err}"),
+            Confirmation::TimedOut => format!("Transaction {signature} was not finalized in time")
+        };
+
+        return Err(format!("\"msg\": {}", serde_json::to_string(&msg).unwrap_or_default()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_of_one_dao_share_a_lock() {
+        let first = get_multisig_lock(r#"{"multisig_pda": "dao-a", "amount": "1"}"#).unwrap();
+        let second = get_multisig_lock(r#"{"multisig_pda": "dao-a", "is_execute": true}"#).unwrap();
+        let other = get_multisig_lock(r#"{"multisig_pda": "dao-b"}"#).unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert!(get_multisig_lock(r#"{"vault_account": "dao-a"}"#).is_none());
+        assert!(get_multisig_lock("not json").is_none());
+    }
+}
@@ -0,0 +1,149 @@
+use std::time::Duration;
+
+use amqprs::callbacks::{ChannelCallback, DefaultConnectionCallback};
+use amqprs::channel::{BasicPublishArguments, Channel, ConfirmSelectArguments, QueueBindArguments, QueueDeclareArguments};
+use amqprs::connection::{Connection, OpenConnectionArguments};
+use amqprs::error::Error as AmqpError;
+use amqprs::{Ack, BasicProperties, Cancel, CloseChannel, Nack, Return, DELIVERY_MODE_PERSISTENT};
+use async_trait::async_trait;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
+
+pub const EVENTS_ROUTING_KEY: &str = "broker.events";
+pub const EVENTS_QUEUE: &str = "events_queue";
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Broker confirmation of a published message, `true` when it was acked
+struct Confirm {
+    delivery_tag: u64,
+    multiple: bool,
+    acked: bool,
+}
+
+/// Forwards publisher confirms of the channel to the publisher waiting for them
+struct ConfirmCallback {
+    confirms: UnboundedSender<Confirm>,
+}
+
+#[async_trait]
+impl ChannelCallback for ConfirmCallback {
+    async fn close(&mut self, _channel: &Channel, close: CloseChannel) -> Result<(), AmqpError> {
+        eprintln!("events channel closed: {close}");
+        Ok(())
+    }
+    async fn cancel(&mut self, _channel: &Channel, _cancel: Cancel) -> Result<(), AmqpError> {
+        Ok(())
+    }
+    async fn flow(&mut self, _channel: &Channel, _active: bool) -> Result<bool, AmqpError> {
+        Ok(true)
+    }
+    async fn publish_ack(&mut self, _channel: &Channel, ack: Ack) {
+        let _ = self.confirms.send(Confirm { delivery_tag: ack.delivery_tag(), multiple: ack.mutiple(), acked: true });
+    }
+    async fn publish_nack(&mut self, _channel: &Channel, nack: Nack) {
+        let _ = self.confirms.send(Confirm { delivery_tag: nack.delivery_tag(), multiple: nack.multiple(), acked: false });
+    }
+    async fn publish_return(&mut self, _channel: &Channel, ret: Return, _basic_properties: BasicProperties, _content: Vec<u8>) {
+        eprintln!("event returned: {ret}");
+    }
+}
+
+/// Publishes DAO events on `broker.events` in publisher confirm mode.
+/// `publish_message` returns only once the broker took responsibility for the message
+pub struct RabbitMQEventPublisher {
+    connection: Connection,
+    channel: Channel,
+    /// Delivery tag of the last published message and the confirms received for the channel
+    state: Mutex<(u64, UnboundedReceiver<Confirm>)>,
+}
+
+impl RabbitMQEventPublisher {
+    pub async fn new(
+        host: &str,
+        port: u16,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, String> {
+        let connection_arguments = OpenConnectionArguments::new(host, port, username, password);
+
+        let connection = Connection::open(&connection_arguments)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        connection
+            .register_callback(DefaultConnectionCallback)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let (confirms, confirms_receiver) = mpsc::unbounded_channel();
+        let channel = connection.open_channel(None).await.map_err(|e| e.to_string())?;
+        channel
+            .register_callback(ConfirmCallback { confirms })
+            .await
+            .map_err(|e| e.to_string())?;
+        channel
+            .confirm_select(ConfirmSelectArguments::default())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // Events published before the backend subscribes wait in a durable queue instead of being dropped
+        let (queue_name, _, _) = channel
+            .queue_declare(QueueDeclareArguments::default().queue(EVENTS_QUEUE.to_string()).durable(true).finish())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Events queue was not declared".to_string())?;
+        channel
+            .queue_bind(QueueBindArguments::new(&queue_name, "dao_exchange", EVENTS_ROUTING_KEY))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            connection,
+            channel,
+            state: Mutex::new((0, confirms_receiver)),
+        })
+    }
+
+    pub async fn publish_message(&self, message: &str) -> Result<(), String> {
+        if !self.connection.is_open() {
+            return Err("Connection is not open".to_string());
+        }
+
+        if !self.channel.is_open() {
+            return Err("Channel is not open".to_string());
+        }
+
+        // One message in flight at a time, so the next confirm covering its tag is the answer
+        let mut state = self.state.lock().await;
+        let (last_delivery_tag, confirms) = &mut *state;
+
+        let args = BasicPublishArguments::new("dao_exchange", EVENTS_ROUTING_KEY);
+        self.channel
+            .basic_publish(
+                BasicProperties::default()
+                    .with_delivery_mode(DELIVERY_MODE_PERSISTENT)
+                    .with_content_type("application/json")
+                    .finish(),
+                message.into(),
+                args,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        *last_delivery_tag += 1;
+        let delivery_tag = *last_delivery_tag;
+
+        loop {
+            let confirm = tokio::time::timeout(CONFIRM_TIMEOUT, confirms.recv())
+                .await
+                .map_err(|_| format!("Broker did not confirm event {delivery_tag}"))?
+                .ok_or_else(|| "Events channel closed".to_string())?;
+
+            if confirm.delivery_tag == delivery_tag || (confirm.multiple && confirm.delivery_tag > delivery_tag) {
+                return match confirm.acked {
+                    true => Ok(()),
+                    false => Err(format!("Broker rejected event {delivery_tag}")),
+                };
+            }
+        }
+    }
+}
@@ -38,7 +38,8 @@ impl RabbitMQPublisher {
         })
     }
 
-    pub async fn publish_message(&self, message: &str) -> Result<(), String> {
+    /// Publishes `message` to `broker.response`, tagged with the correlation id of the request it answers
+    pub async fn publish_message(&self, message: &str, correlation_id: Option<&str>) -> Result<(), String> {
         if !self.connection.is_open() {
             return Err("Connection is not open".to_string());
         }
@@ -49,11 +50,15 @@ impl RabbitMQPublisher {
 
         let args = BasicPublishArguments::new("dao_exchange", "broker.response");
 
+        let mut properties = BasicProperties::default();
+        properties.with_delivery_mode(DELIVERY_MODE_PERSISTENT);
+        if let Some(correlation_id) = correlation_id {
+            properties.with_correlation_id(correlation_id);
+        }
+
         self.channel
             .basic_publish(
-                BasicProperties::default()
-                    .with_delivery_mode(DELIVERY_MODE_PERSISTENT)
-                    .finish(),
+                properties.finish(),
                 message.into(),
                 args,
             )
@@ -5,11 +5,13 @@ use serde::Deserialize;
 pub struct AddMemberDaoSchema {
     multisig_pda: String,
     pubkey: String,
-    permissions: Vec<String>
+    permissions: Vec<String>,
+    #[serde(default)]
+    dry_run: bool
 }
 
 pub async fn consume(request: AddMemberDaoSchema) -> Result<String, String> {
-    let pda = dao_service::add_member(request.multisig_pda.clone(), request.pubkey, request.permissions).await.unwrap();
+    let pda = dao_service::add_member(request.multisig_pda.clone(), request.pubkey, request.permissions, request.dry_run).await?;
     return Ok(format!(
         "\"multisig_pda\": \"{}\",
     {pda}", request.multisig_pda
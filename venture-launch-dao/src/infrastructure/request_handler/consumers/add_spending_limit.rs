@@ -0,0 +1,22 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct AddSpendingLimitDaoSchema {
+    multisig_pda: String,
+    mint: String,
+    amount: u64,
+    period: String,
+    members: Vec<String>,
+    destinations: Vec<String>,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: AddSpendingLimitDaoSchema) -> Result<String, String> {
+    let pda = dao_service::add_spending_limit(request.multisig_pda.clone(), request.mint, request.amount, request.period, request.members, request.destinations, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -0,0 +1,18 @@
+use crate::domain::services::dao_service::{self, BatchConfigAction};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct BatchConfigDaoSchema {
+    multisig_pda: String,
+    actions: Vec<BatchConfigAction>,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: BatchConfigDaoSchema) -> Result<String, String> {
+    let pda = dao_service::batch_config(request.multisig_pda.clone(), request.actions, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -4,11 +4,13 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug)]
 pub struct ChangeThresholdDaoSchema {
     multisig_pda: String,
-    new_threshold: u16
+    new_threshold: u16,
+    #[serde(default)]
+    dry_run: bool
 }
 
 pub async fn consume(request: ChangeThresholdDaoSchema) -> Result<String, String> {
-    let pda = dao_service::change_threshold(request.multisig_pda.clone(), request.new_threshold).await.unwrap();
+    let pda = dao_service::change_threshold(request.multisig_pda.clone(), request.new_threshold, request.dry_run).await?;
     return Ok(format!(
         "\"multisig_pda\": \"{}\",
         {pda}", request.multisig_pda
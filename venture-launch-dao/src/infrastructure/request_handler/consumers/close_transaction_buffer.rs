@@ -0,0 +1,19 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct CloseTransactionBufferDaoSchema {
+    multisig_pda: String,
+    #[serde(default)]
+    buffer_index: u8,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: CloseTransactionBufferDaoSchema) -> Result<String, String> {
+    let pda = dao_service::close_transaction_buffer(request.multisig_pda.clone(), request.buffer_index, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -4,10 +4,12 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug)]
 pub struct CreateDaoSchema {
     project_id: String,
+    #[serde(default)]
+    dry_run: bool
 }
 
 pub async fn consume(request: CreateDaoSchema) -> Result<String, String> {
-    let pda = dao_service::create_dao().await.unwrap();
+    let pda = dao_service::create_dao(request.dry_run).await?;
 
     return Ok(format!(
         "{pda},
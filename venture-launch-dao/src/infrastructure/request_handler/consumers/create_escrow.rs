@@ -0,0 +1,19 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct CreateEscrowSchema {
+    multisig_pda: String,
+    mint: Option<String>,
+    transaction_index: Option<u64>,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: CreateEscrowSchema) -> Result<String, String> {
+    let escrow = dao_service::create_escrow(request.multisig_pda.clone(), request.mint, request.transaction_index, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {escrow}", request.multisig_pda
+    ));
+}
@@ -0,0 +1,18 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct CreateMilestoneDaoSchema {
+    multisig_pda: String,
+    milestone_id: String,
+    amount: u64,
+    recipient: String
+}
+
+pub async fn consume(request: CreateMilestoneDaoSchema) -> Result<String, String> {
+    let pda = dao_service::create_milestone(request.multisig_pda.clone(), request.milestone_id, request.amount, request.recipient).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
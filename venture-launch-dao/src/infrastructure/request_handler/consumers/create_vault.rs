@@ -0,0 +1,13 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct CreateVaultSchema {
+    mint: Option<String>,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: CreateVaultSchema) -> Result<String, String> {
+    dao_service::create_vault(request.mint, request.dry_run).await
+}
@@ -0,0 +1,19 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct DepositToVaultSchema {
+    vault_account: String,
+    data_account: String,
+    amount: u64,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: DepositToVaultSchema) -> Result<String, String> {
+    let vault = dao_service::deposit_to_vault(request.vault_account, request.data_account.clone(), request.amount, request.dry_run).await?;
+    return Ok(format!(
+        "\"data_account\": \"{}\",
+        {vault}", request.data_account
+    ));
+}
@@ -3,11 +3,13 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
 pub struct ProposalExecuteDaoSchema {
-    multisig_pda: String
+    multisig_pda: String,
+    #[serde(default)]
+    dry_run: bool
 }
 
 pub async fn consume(request: ProposalExecuteDaoSchema) -> Result<String, String> {
-    let pda = dao_service::execute_proposal(request.multisig_pda.clone()).await.unwrap();
+    let pda = dao_service::execute_proposal(request.multisig_pda.clone(), request.dry_run).await?;
     return Ok(format!(
         "\"multisig_pda\": \"{}\",
         {pda}", request.multisig_pda
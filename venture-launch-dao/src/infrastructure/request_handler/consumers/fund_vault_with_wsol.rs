@@ -0,0 +1,18 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct FundVaultWithWsolSchema {
+    multisig_pda: String,
+    amount: u64,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: FundVaultWithWsolSchema) -> Result<String, String> {
+    let vault = dao_service::fund_vault_with_wsol(request.multisig_pda.clone(), request.amount, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {vault}", request.multisig_pda
+    ));
+}
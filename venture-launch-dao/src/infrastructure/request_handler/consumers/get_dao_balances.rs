@@ -0,0 +1,15 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct GetDaoBalancesDaoSchema {
+    multisig_pda: String
+}
+
+pub async fn consume(request: GetDaoBalancesDaoSchema) -> Result<String, String> {
+    let pda = dao_service::get_dao_balances(request.multisig_pda.clone()).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
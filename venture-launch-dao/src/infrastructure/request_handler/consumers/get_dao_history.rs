@@ -0,0 +1,17 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct GetDaoHistoryDaoSchema {
+    multisig_pda: String,
+    #[serde(default)]
+    limit: Option<usize>
+}
+
+pub async fn consume(request: GetDaoHistoryDaoSchema) -> Result<String, String> {
+    let pda = dao_service::get_dao_history(request.multisig_pda.clone(), request.limit).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -0,0 +1,9 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct GetDaosDaoSchema {}
+
+pub async fn consume(_request: GetDaosDaoSchema) -> Result<String, String> {
+    dao_service::get_daos().await
+}
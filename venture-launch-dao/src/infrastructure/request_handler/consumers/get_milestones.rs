@@ -0,0 +1,15 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct GetMilestonesDaoSchema {
+    multisig_pda: String
+}
+
+pub async fn consume(request: GetMilestonesDaoSchema) -> Result<String, String> {
+    let pda = dao_service::get_milestones(request.multisig_pda.clone()).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -0,0 +1,16 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct GetProposalDaoSchema {
+    multisig_pda: String,
+    transaction_index: u64
+}
+
+pub async fn consume(request: GetProposalDaoSchema) -> Result<String, String> {
+    let pda = dao_service::get_proposal(request.multisig_pda.clone(), request.transaction_index).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
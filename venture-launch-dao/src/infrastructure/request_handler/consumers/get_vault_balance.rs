@@ -0,0 +1,16 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct GetVaultBalanceSchema {
+    vault_account: String,
+    data_account: String
+}
+
+pub async fn consume(request: GetVaultBalanceSchema) -> Result<String, String> {
+    let vault = dao_service::get_vault_balance(request.vault_account, request.data_account.clone()).await?;
+    return Ok(format!(
+        "\"data_account\": \"{}\",
+        {vault}", request.data_account
+    ));
+}
@@ -1,7 +1,33 @@
 pub mod add_member;
+pub mod add_spending_limit;
+pub mod batch_config;
 pub mod change_threshold;
+pub mod close_nonce_account;
+pub mod close_transaction_buffer;
 pub mod create_dao;
+pub mod create_escrow;
+pub mod create_milestone;
+pub mod create_vault;
+pub mod deposit_to_vault;
 pub mod execute_proposal;
+pub mod fund_vault_with_wsol;
+pub mod get_dao_balances;
+pub mod get_dao_history;
+pub mod get_daos;
+pub mod get_milestones;
+pub mod get_spending_limits;
+pub mod get_proposal;
+pub mod get_vault_balance;
+pub mod prepare_vote;
+pub mod reclaim_rent;
+pub mod refund;
 pub mod remove_member;
+pub mod remove_spending_limit;
+pub mod rotate_nonce_account;
+pub mod submit_signed_vote;
+pub mod unwrap_vault_wsol;
+pub mod use_spending_limit;
 pub mod vote;
-pub mod withdraw;
\ No newline at end of file
+pub mod withdraw;
+pub mod withdraw_from_escrow;
+pub mod withdraw_from_vault;
\ No newline at end of file
@@ -0,0 +1,19 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct PrepareVoteDaoSchema {
+    multisig_pda: String,
+    voter: String,
+    vote: String,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: PrepareVoteDaoSchema) -> Result<String, String> {
+    let pda = dao_service::prepare_vote(request.multisig_pda.clone(), request.voter, request.vote, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -0,0 +1,17 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct ReclaimRentDaoSchema {
+    multisig_pda: String,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: ReclaimRentDaoSchema) -> Result<String, String> {
+    let pda = dao_service::reclaim_rent(request.multisig_pda.clone(), request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
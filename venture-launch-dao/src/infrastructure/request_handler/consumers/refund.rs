@@ -0,0 +1,22 @@
+use crate::domain::services::dao_service::{self, RefundInvestor};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct RefundDaoSchema {
+    multisig_pda: String,
+    #[serde(default)]
+    investors: Vec<RefundInvestor>,
+    snapshot_mint: Option<String>,
+    #[serde(default)]
+    is_execute: bool,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: RefundDaoSchema) -> Result<String, String> {
+    let pda = dao_service::refund(request.multisig_pda.clone(), request.investors, request.snapshot_mint, request.is_execute, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
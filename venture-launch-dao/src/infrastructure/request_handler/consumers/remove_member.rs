@@ -4,11 +4,13 @@ use serde::Deserialize;
 #[derive(Deserialize, Debug)]
 pub struct RemoveMemberDaoSchema {
     multisig_pda: String,
-    pubkey: String
+    pubkey: String,
+    #[serde(default)]
+    dry_run: bool
 }
 
 pub async fn consume(request: RemoveMemberDaoSchema) -> Result<String, String> {
-    let pda = dao_service::remove_member(request.multisig_pda.clone(), request.pubkey).await.unwrap();
+    let pda = dao_service::remove_member(request.multisig_pda.clone(), request.pubkey, request.dry_run).await?;
     return Ok(format!(
         "\"multisig_pda\": \"{}\",
     {pda}", request.multisig_pda
@@ -0,0 +1,18 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct RemoveSpendingLimitDaoSchema {
+    multisig_pda: String,
+    spending_limit: String,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: RemoveSpendingLimitDaoSchema) -> Result<String, String> {
+    let pda = dao_service::remove_spending_limit(request.multisig_pda.clone(), request.spending_limit, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
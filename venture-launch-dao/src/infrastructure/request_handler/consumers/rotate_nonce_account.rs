@@ -0,0 +1,18 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct RotateNonceAccountDaoSchema {
+    multisig_pda: String,
+    member: String,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: RotateNonceAccountDaoSchema) -> Result<String, String> {
+    let pda = dao_service::rotate_nonce_account(request.multisig_pda.clone(), request.member, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -0,0 +1,19 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct SubmitSignedVoteDaoSchema {
+    multisig_pda: String,
+    voter: String,
+    transaction: String,
+    commitment: Option<String>,
+    timeout_ms: Option<u64>
+}
+
+pub async fn consume(request: SubmitSignedVoteDaoSchema) -> Result<String, String> {
+    let pda = dao_service::submit_signed_vote(request.multisig_pda.clone(), request.voter, request.transaction, request.commitment, request.timeout_ms).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -0,0 +1,18 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct UnwrapVaultWsolSchema {
+    multisig_pda: String,
+    transaction_index: Option<u64>,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: UnwrapVaultWsolSchema) -> Result<String, String> {
+    let vault = dao_service::unwrap_vault_wsol(request.multisig_pda.clone(), request.transaction_index, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {vault}", request.multisig_pda
+    ));
+}
@@ -0,0 +1,20 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct UseSpendingLimitDaoSchema {
+    multisig_pda: String,
+    spending_limit: String,
+    receiver: String,
+    amount: u64,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: UseSpendingLimitDaoSchema) -> Result<String, String> {
+    let pda = dao_service::use_spending_limit(request.multisig_pda.clone(), request.spending_limit, request.receiver, request.amount, request.dry_run).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {pda}", request.multisig_pda
+    ));
+}
@@ -5,11 +5,13 @@ use serde::Deserialize;
 pub struct VoteDaoSchema {
     multisig_pda: String,
     voter: String,
-    vote: String
+    vote: String,
+    #[serde(default)]
+    dry_run: bool
 }
 
 pub async fn consume(request: VoteDaoSchema) -> Result<String, String> {
-    let pda = dao_service::vote(request.multisig_pda.clone(), request.voter, request.vote).await.unwrap();
+    let pda = dao_service::vote(request.multisig_pda.clone(), request.voter, request.vote, request.dry_run).await?;
     return Ok(format!(
         "\"multisig_pda\": \"{}\",
         {pda}", request.multisig_pda
@@ -1,3 +1,4 @@
+use crate::domain::entities::token::amount::TokenAmount;
 use crate::domain::services::dao_service;
 use serde::Deserialize;
 
@@ -7,12 +8,15 @@ pub struct WithdrawDaoSchema {
     proposal_id: String,
     is_execute: bool,
     receiver: String,
-    amount: u64
+    amount: TokenAmount,
+    mint: Option<String>,
+    #[serde(default)]
+    dry_run: bool
 }
 
 
 pub async fn consume(request: WithdrawDaoSchema) -> Result<String, String> {
-    let pda = dao_service::withdraw(request.multisig_pda.clone(), request.is_execute, request.receiver, request.amount).await.unwrap();
+    let pda = dao_service::withdraw(request.multisig_pda.clone(), request.proposal_id.clone(), request.is_execute, request.receiver, request.amount, request.mint, request.dry_run).await?;
     return Ok(format!(
         "\"multisig_pda\": \"{}\",
         \"proposal_id\": \"{}\",
@@ -0,0 +1,30 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct WithdrawFromEscrowSchema {
+    multisig_pda: String,
+    vault_account: String,
+    data_account: String,
+    receiver: String,
+    amount: u64,
+    transaction_index: Option<u64>,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: WithdrawFromEscrowSchema) -> Result<String, String> {
+    let escrow = dao_service::withdraw_from_escrow(
+        request.multisig_pda.clone(),
+        request.vault_account,
+        request.data_account,
+        request.receiver,
+        request.amount,
+        request.transaction_index,
+        request.dry_run
+    ).await?;
+    return Ok(format!(
+        "\"multisig_pda\": \"{}\",
+        {escrow}", request.multisig_pda
+    ));
+}
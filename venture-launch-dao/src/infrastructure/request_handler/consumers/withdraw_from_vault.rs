@@ -0,0 +1,20 @@
+use crate::domain::services::dao_service;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct WithdrawFromVaultSchema {
+    vault_account: String,
+    data_account: String,
+    receiver: String,
+    amount: u64,
+    #[serde(default)]
+    dry_run: bool
+}
+
+pub async fn consume(request: WithdrawFromVaultSchema) -> Result<String, String> {
+    let vault = dao_service::withdraw_from_vault(request.vault_account, request.data_account.clone(), request.receiver, request.amount, request.dry_run).await?;
+    return Ok(format!(
+        "\"data_account\": \"{}\",
+        {vault}", request.data_account
+    ));
+}
@@ -1,10 +1,12 @@
 pub mod infrastructure;
 pub mod domain;
+pub mod contract_module;
 
 use dotenv::dotenv;
 use std::error::Error;
 use tokio;
 
+use infrastructure::event_indexer::{EventIndexer, EventIndexerInitArgs};
 use infrastructure::rabbitMQ_utils::broker::BrokerInitArgs;
 use crate::infrastructure::rabbitMQ_utils::broker::Broker;
 
@@ -16,6 +18,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let rabbit_broker = Broker::new(
             BrokerInitArgs {
+                host: host.clone(),
+                port: 5672,
+                username: String::from("guest"),
+                password: String::from("guest")
+            }
+        ).await.unwrap();
+
+    let event_indexer = EventIndexer::new(
+            EventIndexerInitArgs {
                 host,
                 port: 5672,
                 username: String::from("guest"),
@@ -23,7 +34,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
             }
         ).await.unwrap();
 
-        let _ = tokio::join!(rabbit_broker.start_broker());
+        let _ = tokio::join!(rabbit_broker.start_broker(), event_indexer.start_indexer());
 
     Ok(())
 }